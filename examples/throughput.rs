@@ -0,0 +1,34 @@
+// parse throughput of the scanner against the pest grammar
+//
+// cargo run --release --example throughput [ITERATIONS]
+
+use netdec::{matches_range_any, CompactRange, IpRange};
+use std::hint::black_box;
+use std::time::Instant;
+
+const INPUTS: &[&str] = &[
+  "192.168.0.0/24", "10.0.0.1-10.0.0.200", "10.*", "172.16.5.4", "10.0.0.0+256", "10.0.0.0/24[10:20]", "10.1.*.*",
+];
+
+fn measure(name: &str, iterations: usize, mut f: impl FnMut(&str) -> bool) {
+  let start = Instant::now();
+  let mut ok = 0;
+  for _ in 0..iterations {
+    for input in INPUTS {
+      ok += f(black_box(input)) as usize;
+    }
+  }
+  let elapsed = start.elapsed();
+  let total = iterations * INPUTS.len();
+  assert_eq!(ok, total);
+
+  println!("{name:<24} {:>12.0} ranges/s", total as f64 / elapsed.as_secs_f64());
+}
+
+fn main() {
+  let iterations = std::env::args().nth(1).and_then(|n| n.parse().ok()).unwrap_or(200_000);
+
+  measure("pest range_any match", iterations, matches_range_any);
+  measure("IpRange::parse", iterations, |s| IpRange::parse(s).is_ok());
+  measure("CompactRange::parse", iterations, |s| CompactRange::parse(s).is_ok());
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::list::strip_comment;
+use crate::{full_match, IpRangeError, IpRangeSet, ParseOptions, Rule};
+
+// named ranges, each defined by a list as accepted by IpRangeSet::parse
+#[derive(Debug, Clone, Default)]
+pub struct Aliases {
+  defs: HashMap<String, String>,
+  opts: ParseOptions, // policy for definitions and for lists using them
+}
+
+// state of one resolution, shared aliases are resolved once
+#[derive(Debug, Default)]
+pub(crate) struct Resolving {
+  stack: Vec<String>, // aliases currently being resolved, to report cycles
+  done: HashMap<String, IpRangeSet>,
+}
+
+
+// ===== core logic =====
+
+
+impl Resolving {
+  pub(crate) fn nested(&self) -> bool {
+    // inside the definition of an alias
+    !self.stack.is_empty()
+  }
+}
+
+impl Aliases {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /*
+  parse a definitions file, one alias per line
+
+  ex.
+    # shared network objects
+    office = 10.1.0.0/16
+    vpn = 10.8.*
+    trusted = office, vpn, !10.1.99.0/24
+
+  definitions may reference other aliases in any order,
+  "#" and ";" start a comment and blank lines are ignored
+  */
+
+  pub fn parse(text: &str) -> Result<Self, IpRangeError> {
+    let mut aliases = Aliases::new();
+
+    for (i, line) in text.lines().enumerate() {
+      let line = strip_comment(line).trim();
+      if line.is_empty() {
+        continue;
+      }
+
+      let syntax = |message: String| IpRangeError::AliasSyntax { line: i + 1, message };
+      let (name, value) = line.split_once('=').ok_or_else(|| syntax("expected name = ranges".to_string()))?;
+      let (name, value) = (name.trim(), value.trim());
+
+      if !full_match(Rule::alias_name, name) {
+        return Err(syntax(format!("invalid alias name {name:?}")));
+      }
+      if aliases.defs.contains_key(name) {
+        return Err(syntax(format!("alias {name} is defined twice")));
+      }
+      aliases.define(name, value);
+    }
+
+    Ok(aliases)
+  }
+
+  pub fn load(path: impl AsRef<Path>) -> Result<Self, IpRangeError> {
+    // read and parse a definitions file
+    Self::parse(&fs::read_to_string(path)?)
+  }
+
+  pub fn with_options(mut self, opts: ParseOptions) -> Self {
+    // parse definitions as in IpRange::parse_with, this also applies to the
+    // lists and expressions given these aliases, ex. IpRangeSet::eval_with_aliases
+    self.opts = opts;
+    self
+  }
+
+  pub(crate) fn options(&self) -> &ParseOptions {
+    &self.opts
+  }
+
+  pub fn define(&mut self, name: &str, value: &str) {
+    // add or replace a single definition
+    self.defs.insert(name.to_string(), value.to_string());
+  }
+
+  pub fn get(&self, name: &str) -> Option<&str> {
+    self.defs.get(name).map(String::as_str)
+  }
+
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.defs.keys().map(String::as_str)
+  }
+
+  pub fn resolve(&self, name: &str) -> Result<IpRangeSet, IpRangeError> {
+    // the set of addresses an alias stands for
+    self.resolve_inner(name, &mut Resolving::default())
+  }
+
+  pub(crate) fn resolve_inner(&self, name: &str, state: &mut Resolving) -> Result<IpRangeSet, IpRangeError> {
+    // resolve an alias, or reuse it when an earlier reference already did
+    let value = self.get(name).ok_or_else(|| IpRangeError::UndefinedAlias(name.to_string()))?;
+    if let Some(set) = state.done.get(name) {
+      return Ok(set.clone());
+    }
+
+    if let Some(pos) = state.stack.iter().position(|n| n == name) {
+      let mut cycle = state.stack[pos..].to_vec();
+      cycle.push(name.to_string());
+      return Err(IpRangeError::AliasCycle(cycle));
+    }
+
+    state.stack.push(name.to_string());
+    let parsed = IpRangeSet::parse_list(value, Some(self), &self.opts, state);
+    state.stack.pop();
+
+    // errors about other aliases already name them, everything else is wrapped
+    match parsed.errors.into_iter().next() {
+      None => {
+        state.done.insert(name.to_string(), parsed.set.clone());
+        Ok(parsed.set)
+      }
+      Some(e) => match e.error {
+        err @ (IpRangeError::UndefinedAlias(_) | IpRangeError::AliasCycle(_) | IpRangeError::InAlias { .. }) => Err(err),
+        err => Err(IpRangeError::InAlias { name: name.to_string(), source: Box::new(err) }),
+      },
+    }
+  }
+}
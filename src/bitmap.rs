@@ -0,0 +1,248 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::net::Ipv4Addr;
+use std::path::Path;
+
+use roaring::RoaringBitmap;
+
+use crate::{IpRange, IpRangeError, IpRangeSet};
+
+/*
+a set of IPv4 addresses backed by a compressed roaring bitmap
+
+IpRangeSet stores one interval per run of consecutive addresses, which
+is ideal for networks but costs 8 bytes per scattered host. the bitmap
+stores scattered hosts in about 2 bytes each, and long runs compactly too
+
+ex. millions of individual hosts seen in logs, minus a blocklist
+*/
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressBitmap {
+  bits: RoaringBitmap,
+}
+
+
+// ===== core logic =====
+
+
+impl AddressBitmap {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn insert(&mut self, ip: Ipv4Addr) -> bool {
+    // true if the address was not in the bitmap yet
+    self.bits.insert(u32::from(ip))
+  }
+
+  pub fn remove(&mut self, ip: Ipv4Addr) -> bool {
+    // true if the address was in the bitmap
+    self.bits.remove(u32::from(ip))
+  }
+
+  pub fn contains(&self, ip: Ipv4Addr) -> bool {
+    self.bits.contains(u32::from(ip))
+  }
+
+  pub fn insert_range(&mut self, range: &IpRange) -> u64 {
+    // add every address of the range, returns how many were new
+    self.bits.insert_range(u32::from(range.first)..=u32::from(range.last))
+  }
+
+  pub fn remove_range(&mut self, range: &IpRange) -> u64 {
+    // remove every address of the range, returns how many were present
+    self.bits.remove_range(u32::from(range.first)..=u32::from(range.last))
+  }
+
+  pub fn contains_range(&self, range: &IpRange) -> bool {
+    self.bits.contains_range(u32::from(range.first)..=u32::from(range.last))
+  }
+
+  pub fn size(&self) -> u128 {
+    // number of IPv4 addresses in the bitmap
+    self.bits.len() as u128
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.bits.is_empty()
+  }
+
+  pub fn first(&self) -> Option<Ipv4Addr> {
+    self.bits.min().map(Ipv4Addr::from)
+  }
+
+  pub fn last(&self) -> Option<Ipv4Addr> {
+    self.bits.max().map(Ipv4Addr::from)
+  }
+
+  /*
+  rank and select, the bitmap seen as a sorted list of addresses
+
+  rank(ip) is the number of addresses <= ip, select(n) is the address at
+  index n, so select(rank(ip) - 1) == ip for every ip in the bitmap
+  */
+
+  pub fn rank(&self, ip: Ipv4Addr) -> u64 {
+    self.bits.rank(u32::from(ip))
+  }
+
+  pub fn select(&self, n: u64) -> Option<Ipv4Addr> {
+    let n = u32::try_from(n).ok()?;
+    self.bits.select(n).map(Ipv4Addr::from)
+  }
+
+  pub fn union(&self, other: &AddressBitmap) -> AddressBitmap {
+    // addresses in either bitmap
+    AddressBitmap { bits: &self.bits | &other.bits }
+  }
+
+  pub fn intersection(&self, other: &AddressBitmap) -> AddressBitmap {
+    // addresses in both bitmaps
+    AddressBitmap { bits: &self.bits & &other.bits }
+  }
+
+  pub fn difference(&self, other: &AddressBitmap) -> AddressBitmap {
+    // addresses in self but not in other
+    AddressBitmap { bits: &self.bits - &other.bits }
+  }
+
+  pub fn symmetric_difference(&self, other: &AddressBitmap) -> AddressBitmap {
+    // addresses in exactly one of the bitmaps
+    AddressBitmap { bits: &self.bits ^ &other.bits }
+  }
+
+  pub fn complement(&self) -> AddressBitmap {
+    // every IPv4 address not in the bitmap
+    AddressBitmap { bits: RoaringBitmap::full() - &self.bits }
+  }
+
+  pub fn iter(&self) -> impl DoubleEndedIterator<Item = Ipv4Addr> + '_ {
+    // every address, in ascending order
+    self.bits.iter().map(Ipv4Addr::from)
+  }
+
+  pub fn ranges(&self) -> impl Iterator<Item = IpRange> + '_ {
+    // runs of consecutive addresses, in ascending order, as IpRangeSet::ranges
+    let mut it = self.bits.iter();
+    std::iter::from_fn(move || it.next_range()).map(|run| IpRange::canonical(*run.start(), *run.end()))
+  }
+
+  pub fn to_set(&self) -> IpRangeSet {
+    self.ranges().collect()
+  }
+}
+
+
+// ===== serialization =====
+
+
+/*
+the portable roaring format, shared with the CRoaring, Java and Go
+implementations. a copy of the bitmap is run compressed before writing,
+so whole networks stay small whatever operations built them
+*/
+
+impl AddressBitmap {
+  pub fn serialized_size(&self) -> usize {
+    self.optimized().serialized_size()
+  }
+
+  pub fn write_to(&self, writer: impl Write) -> Result<(), IpRangeError> {
+    Ok(self.optimized().serialize_into(writer)?)
+  }
+
+  fn optimized(&self) -> RoaringBitmap {
+    // range and set operations leave runs as arrays or bitsets
+    let mut bits = self.bits.clone();
+    bits.optimize();
+    bits
+  }
+
+  pub fn from_reader(reader: impl Read) -> Result<Self, IpRangeError> {
+    // fails with an io error on truncated or corrupt input
+    Ok(AddressBitmap { bits: RoaringBitmap::deserialize_from(reader)? })
+  }
+
+  pub fn save(&self, path: impl AsRef<Path>) -> Result<(), IpRangeError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    self.write_to(&mut writer)?;
+    Ok(writer.flush()?)
+  }
+
+  pub fn load(path: impl AsRef<Path>) -> Result<Self, IpRangeError> {
+    Self::from_reader(BufReader::new(File::open(path)?))
+  }
+}
+
+
+// ===== type conversions =====
+
+
+impl FromIterator<Ipv4Addr> for AddressBitmap {
+  fn from_iter<I: IntoIterator<Item = Ipv4Addr>>(iter: I) -> Self {
+    AddressBitmap { bits: iter.into_iter().map(u32::from).collect() }
+  }
+}
+
+impl FromIterator<IpRange> for AddressBitmap {
+  fn from_iter<I: IntoIterator<Item = IpRange>>(iter: I) -> Self {
+    let mut bitmap = AddressBitmap::new();
+    bitmap.extend(iter);
+    bitmap
+  }
+}
+
+impl<'a> FromIterator<&'a IpRange> for AddressBitmap {
+  fn from_iter<I: IntoIterator<Item = &'a IpRange>>(iter: I) -> Self {
+    let mut bitmap = AddressBitmap::new();
+    bitmap.extend(iter);
+    bitmap
+  }
+}
+
+impl Extend<Ipv4Addr> for AddressBitmap {
+  fn extend<I: IntoIterator<Item = Ipv4Addr>>(&mut self, iter: I) {
+    self.bits.extend(iter.into_iter().map(u32::from));
+  }
+}
+
+impl Extend<IpRange> for AddressBitmap {
+  fn extend<I: IntoIterator<Item = IpRange>>(&mut self, iter: I) {
+    for r in iter {
+      self.insert_range(&r);
+    }
+  }
+}
+
+impl<'a> Extend<&'a IpRange> for AddressBitmap {
+  fn extend<I: IntoIterator<Item = &'a IpRange>>(&mut self, iter: I) {
+    for r in iter {
+      self.insert_range(r);
+    }
+  }
+}
+
+impl From<&IpRange> for AddressBitmap {
+  fn from(range: &IpRange) -> Self {
+    let mut bitmap = AddressBitmap::new();
+    bitmap.insert_range(range);
+    bitmap
+  }
+}
+
+impl From<&IpRangeSet> for AddressBitmap {
+  fn from(set: &IpRangeSet) -> Self {
+    let mut bits = RoaringBitmap::new();
+    for &(a, b) in set.intervals() {
+      bits.insert_range(a..=b);
+    }
+    AddressBitmap { bits }
+  }
+}
+
+impl From<&AddressBitmap> for IpRangeSet {
+  fn from(bitmap: &AddressBitmap) -> Self {
+    bitmap.to_set()
+  }
+}
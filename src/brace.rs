@@ -0,0 +1,146 @@
+use crate::diagnostic::from_grammar;
+use crate::{full_match, IpRange, IpRangeError, ParseOptions, Rule};
+
+#[derive(Clone)]
+enum Segment {
+  Literal(String), // text copied as is, ex. "10." or "/24"
+  Choice(Vec<u8>) // expanded brace values, ex. {1..3} -> [1, 2, 3]
+}
+
+// lazy cartesian product over every brace in the input
+#[derive(Clone)]
+pub struct BraceExpansion {
+  segments: Vec<Segment>,
+  idx: Vec<usize>,
+  done: bool,
+  opts: ParseOptions, // every expanded range is parsed with these
+}
+
+
+// ===== core logic =====
+
+
+impl IpRange {
+
+  /*
+  expand shell like braces into every range they describe, without a shell
+
+  supports lists "{0,2,4}" and inclusive sequences "{1..3}" in any octet
+  and in the cidr prefix, ex. "10.{0,2}.{1..3}.0/24" or "192.168.1.{10,20}".
+  input without braces expands to itself
+  */
+
+  pub fn expand(input: &str) -> Result<BraceExpansion, IpRangeError> {
+    Self::expand_with(input, &ParseOptions::default())
+  }
+
+  pub fn expand_with(input: &str, opts: &ParseOptions) -> Result<BraceExpansion, IpRangeError> {
+    // same as expand, but every range is parsed as in IpRange::parse_with.
+    // expansions are checked up front, so ex. a rejected host bit fails here
+    let expansion = Self::expand_braces(&opts.normalize(input), opts)?;
+    if *opts != ParseOptions::default() {
+      // the grammar and brace bounds already guarantee the default policy
+      let mut check = expansion.clone();
+      while let Some(s) = check.next_input() {
+        IpRange::parse_with(&s, opts)?;
+      }
+    }
+    Ok(expansion)
+  }
+
+  fn expand_braces(input: &str, opts: &ParseOptions) -> Result<BraceExpansion, IpRangeError> {
+    if !full_match(Rule::range_brace, input) {
+      return Err(IpRangeError::Syntax(Box::new(from_grammar(input, Rule::range_brace))));
+    }
+
+    let mut segments = Vec::new();
+    let mut rest = input;
+    while let Some(open) = rest.find('{') {
+      let close = open + rest[open..].find('}').expect("validated by grammar");
+
+      // braces after the slash are prefix lengths, all others are octets
+      let max = if rest[..open].ends_with('/') { 32 } else { 255 };
+
+      segments.push(Segment::Literal(rest[..open].to_string()));
+      segments.push(Segment::Choice(expand_brace(&rest[open + 1..close], max)?));
+      rest = &rest[close + 1..];
+    }
+    segments.push(Segment::Literal(rest.to_string()));
+
+    let idx = vec![0; segments.len()];
+    Ok(BraceExpansion { segments, idx, done: false, opts: opts.clone() })
+  }
+}
+
+fn expand_brace(body: &str, max: u8) -> Result<Vec<u8>, IpRangeError> {
+  // expand the comma separated items of a single brace, ex. "1..3,7"
+  let value = |s: &str| match s.parse::<u64>() {
+    Ok(v) if v <= max as u64 => Ok(v as u8),
+    _ => Err(IpRangeError::InvalidBrace(format!("{s} exceeds {max}"))),
+  };
+
+  let mut out = Vec::new();
+  for item in body.split(',') {
+    match item.split_once("..") {
+      // sequences may count down like in bash, ex. {3..1}
+      Some((a, b)) => {
+        let (a, b) = (value(a)?, value(b)?);
+        if a <= b {
+          out.extend(a..=b);
+        } else {
+          out.extend((b..=a).rev());
+        }
+      }
+      None => out.push(value(item)?),
+    }
+  }
+
+  Ok(out)
+}
+
+
+// ===== iterator logic =====
+
+
+impl BraceExpansion {
+  fn next_input(&mut self) -> Option<String> {
+    // text of the next expansion, ex. "10.0.1.0/24"
+    if self.done {
+      return None;
+    }
+
+    let mut s = String::new();
+    for (seg, &i) in self.segments.iter().zip(&self.idx) {
+      match seg {
+        Segment::Literal(lit) => s.push_str(lit),
+        Segment::Choice(vals) => s.push_str(&vals[i].to_string()),
+      }
+    }
+
+    // advance like an odometer, the rightmost brace moves fastest
+    self.done = true;
+    for (seg, i) in self.segments.iter().zip(self.idx.iter_mut()).rev() {
+      if let Segment::Choice(vals) = seg {
+        *i += 1;
+        if *i < vals.len() {
+          self.done = false;
+          break;
+        }
+        *i = 0;
+      }
+    }
+
+    Some(s)
+  }
+}
+
+impl Iterator for BraceExpansion {
+  type Item = IpRange;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let s = self.next_input()?;
+    Some(IpRange::parse_with(&s, &self.opts).expect("validated by expand_with"))
+  }
+}
+
+impl std::iter::FusedIterator for BraceExpansion {}
@@ -0,0 +1,134 @@
+use std::io::{self, BufRead, Read};
+use std::vec;
+
+use crate::list::strip_comment;
+use crate::{CompactRange, IpRangeError};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// one non blank line of a bulk input
+#[derive(Debug)]
+pub struct BulkEntry {
+  pub line: usize, // 1-based line number
+  pub result: Result<CompactRange, IpRangeError>,
+}
+
+// lazily parses a reader a chunk of lines at a time, see stream_bulk
+pub struct BulkStream<R> {
+  reader: R,
+  chunk_lines: usize,
+  line: usize, // lines read so far
+  buf: Vec<u8>, // the current chunk, reused across chunks
+  pending: vec::IntoIter<BulkEntry>,
+  done: bool,
+}
+
+
+// ===== core logic =====
+
+
+/*
+parse one range per line from a byte buffer, as found in large blocklists
+
+"#" and ";" start a comment, surrounding whitespace, blank lines and crlf
+line endings are ignored. lines are parsed in parallel with the rayon
+feature, results keep the input order either way
+*/
+
+pub fn parse_bulk(input: &[u8]) -> Vec<BulkEntry> {
+  let lines: Vec<(usize, &[u8])> = input.split(|&b| b == b'\n').enumerate().map(|(i, l)| (i + 1, l)).collect();
+  parse_lines(&lines)
+}
+
+pub fn parse_bulk_reader<R: Read>(mut reader: R) -> io::Result<Vec<BulkEntry>> {
+  // read everything, then parse as parse_bulk
+  let mut input = Vec::new();
+  reader.read_to_end(&mut input)?;
+  Ok(parse_bulk(&input))
+}
+
+pub fn stream_bulk<R: BufRead>(reader: R, chunk_lines: usize) -> BulkStream<R> {
+  // same as parse_bulk, but holds at most chunk_lines lines in memory
+  BulkStream {
+    reader,
+    chunk_lines: chunk_lines.max(1),
+    line: 0,
+    buf: Vec::new(),
+    pending: Vec::new().into_iter(),
+    done: false,
+  }
+}
+
+fn parse_lines(lines: &[(usize, &[u8])]) -> Vec<BulkEntry> {
+  #[cfg(feature = "rayon")]
+  return lines.par_iter().filter_map(|&(line, bytes)| parse_line(line, bytes)).collect();
+
+  #[cfg(not(feature = "rayon"))]
+  lines.iter().filter_map(|&(line, bytes)| parse_line(line, bytes)).collect()
+}
+
+fn parse_line(line: usize, bytes: &[u8]) -> Option<BulkEntry> {
+  // invalid utf-8 is replaced with U+FFFD by from_utf8_lossy, so the line fails to parse and is reported
+  let text = String::from_utf8_lossy(bytes);
+  let text = if line == 1 { text.trim_start_matches('\u{feff}') } else { &text };
+  let item = strip_comment(text).trim();
+  if item.is_empty() {
+    return None;
+  }
+
+  Some(BulkEntry { line, result: CompactRange::parse(item) })
+}
+
+impl<R: BufRead> BulkStream<R> {
+  fn fill(&mut self) -> io::Result<()> {
+    // read and parse the next chunk of lines
+    self.buf.clear();
+    let mut ends = Vec::with_capacity(self.chunk_lines);
+
+    while ends.len() < self.chunk_lines {
+      if self.reader.read_until(b'\n', &mut self.buf)? == 0 {
+        self.done = true;
+        break;
+      }
+      if self.buf.last() == Some(&b'\n') {
+        self.buf.pop();
+      }
+      ends.push(self.buf.len());
+    }
+
+    let mut start = 0;
+    let lines: Vec<(usize, &[u8])> = ends
+      .iter()
+      .enumerate()
+      .map(|(i, &end)| {
+        let line = (self.line + i + 1, &self.buf[start..end]);
+        start = end;
+        line
+      })
+      .collect();
+
+    self.line += ends.len();
+    self.pending = parse_lines(&lines).into_iter();
+    Ok(())
+  }
+}
+
+impl<R: BufRead> Iterator for BulkStream<R> {
+  type Item = io::Result<BulkEntry>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(entry) = self.pending.next() {
+        return Some(Ok(entry));
+      }
+      if self.done {
+        return None;
+      }
+      if let Err(e) = self.fill() {
+        self.done = true;
+        return Some(Err(e));
+      }
+    }
+  }
+}
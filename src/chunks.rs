@@ -0,0 +1,156 @@
+use std::iter::FusedIterator;
+use std::net::Ipv4Addr;
+
+use crate::{mask_from_prefix, IpRange};
+
+// every nth address of a range, see IpRange::iter_step
+#[derive(Debug, Clone)]
+pub struct StepIter {
+  first: u64,
+  step: u64,
+  front: u64, // index of the next address from the front
+  back: u64, // one past the index of the next address from the back
+}
+
+// consecutive sub ranges of k addresses, see IpRange::chunks
+#[derive(Debug, Clone)]
+pub struct Chunks {
+  first: u64,
+  last: u64,
+  size: u64, // addresses per chunk
+  front: u64,
+  back: u64,
+}
+
+// the range split on aligned block boundaries, see IpRange::blocks
+#[derive(Debug, Clone)]
+pub struct Blocks {
+  first: u64,
+  last: u64,
+  base: u64, // start of the aligned block holding first
+  prefix: u8,
+  front: u64,
+  back: u64,
+}
+
+
+// ===== core logic =====
+
+
+impl IpRange {
+  pub fn iter_step(&self, step: u64) -> StepIter {
+    // first, first + step, first + 2 * step, ... up to last
+    assert!(step != 0, "step must be non zero");
+    let size = self.size as u64;
+    StepIter { first: u32::from(self.first) as u64, step, front: 0, back: (size - 1) / step + 1 }
+  }
+
+  pub fn chunks(&self, size: u64) -> Chunks {
+    // sub ranges of size addresses, the last one may be shorter.
+    // a size past the range is one chunk, which also keeps chunk() from overflowing
+    assert!(size != 0, "chunk size must be non zero");
+    Chunks {
+      first: u32::from(self.first) as u64,
+      last: u32::from(self.last) as u64,
+      size: size.min(self.size as u64),
+      front: 0,
+      back: (self.size as u64).div_ceil(size),
+    }
+  }
+
+  /*
+  the parts of the range inside every aligned block of the given prefix,
+  the first and last parts are clipped to the range
+
+  ex. blocks(24) of 10.0.0.5-10.0.2.9
+    10.0.0.5-10.0.0.255, 10.0.1.0/24, 10.0.2.0-10.0.2.9
+  */
+
+  pub fn blocks(&self, prefix: u8) -> Blocks {
+    assert!(prefix <= 32, "prefix must be at most 32");
+    let mask = mask_from_prefix(prefix) as u64;
+    let first = u32::from(self.first) as u64;
+    let last = u32::from(self.last) as u64;
+    let count = (((last & mask) - (first & mask)) >> (32 - prefix)) + 1;
+
+    Blocks { first, last, base: first & mask, prefix, front: 0, back: count }
+  }
+}
+
+impl Chunks {
+  fn chunk(&self, i: u64) -> IpRange {
+    let start = self.first + i * self.size;
+    let end = (start + self.size - 1).min(self.last);
+    IpRange::canonical(start as u32, end as u32)
+  }
+}
+
+impl Blocks {
+  fn block(&self, i: u64) -> IpRange {
+    let block_size = 1u64 << (32 - self.prefix);
+    let block = self.base + i * block_size;
+    let start = block.max(self.first) as u32;
+    let end = (block + block_size - 1).min(self.last) as u32;
+    IpRange::canonical(start, end)
+  }
+}
+
+
+// ===== iterators =====
+
+
+// front and back indices shared by every iterator here, item(i) builds the ith item
+macro_rules! indexed_iterator {
+  ($ty:ty, $item:ty, |$it:ident, $i:ident| $build:expr) => {
+    impl Iterator for $ty {
+      type Item = $item;
+
+      fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+          return None;
+        }
+        let ($it, $i) = (&*self, self.front);
+        let item = $build;
+        self.front += 1;
+        Some(item)
+      }
+
+      fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.back - self.front) {
+          Ok(n) => (n, Some(n)),
+          Err(_) => (usize::MAX, None),
+        }
+      }
+
+      fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n as u64).min(self.back);
+        self.next()
+      }
+    }
+
+    impl DoubleEndedIterator for $ty {
+      fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+          return None;
+        }
+        self.back -= 1;
+        let ($it, $i) = (&*self, self.back);
+        Some($build)
+      }
+
+      fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back = self.back.saturating_sub(n as u64).max(self.front);
+        self.next_back()
+      }
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    impl ExactSizeIterator for $ty {}
+
+    impl FusedIterator for $ty {}
+  };
+}
+
+indexed_iterator!(StepIter, Ipv4Addr, |it, i| Ipv4Addr::from((it.first + i * it.step) as u32));
+indexed_iterator!(Chunks, IpRange, |it, i| it.chunk(i));
+indexed_iterator!(Blocks, IpRange, |it, i| it.block(i));
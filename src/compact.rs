@@ -0,0 +1,441 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::net::Ipv4Addr;
+
+use crate::inet_aton::address_token;
+use crate::{diagnose, infer_cidr_from_range, mask_from_prefix, parse_ipv4_with, AddressForm, AddressMode, IpRange, IpRangeError, Notation, RangeKind, Rule};
+
+// a range without its input string or derived fields, cheap to copy and parse.
+// as with IpRange, equality, ordering and hashing only look at the covered interval
+#[derive(Debug, Clone, Copy)]
+pub struct CompactRange {
+  pub first: Ipv4Addr,
+  pub last: Ipv4Addr,
+  pub kind: RangeKind,
+}
+
+// result of scanning an input the grammar accepts
+pub(crate) struct Scanned {
+  pub(crate) notation: Notation,
+  pub(crate) base: Option<Notation>, // notation of the range a slice indexes into
+  pub(crate) cidr: Option<(Ipv4Addr, u8)>, // address and prefix of a cidr, on its own or as a slice base
+  pub(crate) range: Result<CompactRange, IpRangeError>,
+}
+
+// notation recognized by the scanner, before any semantic checks
+enum Syntax {
+  Single(u32),
+  Cidr(u32, u8),
+  Hyphen(u32, u32),
+  Wildcard([u8; 4], u8),
+  Pattern([Option<u8>; 4]),
+}
+
+struct Cursor<'a> {
+  input: &'a str,
+  pos: usize,
+  mode: Option<AddressMode>, // inet_aton forms of addresses, plain dotted quads if None
+  error: Option<IpRangeError>, // first ambiguous address in strict mode
+}
+
+
+// ===== core logic =====
+
+
+impl CompactRange {
+  /*
+  parse any notation IpRange::parse supports without allocating,
+  only errors allocate
+
+  ex.
+    let r = CompactRange::parse("10.0.0.0/24")?;
+    let details = r.details(); // the full IpRange, on demand
+  */
+
+  pub fn parse(input: &str) -> Result<Self, IpRangeError> {
+    match scan(input) {
+      Some(scanned) => scanned.range,
+      None => Err(IpRangeError::Syntax(Box::new(diagnose(input, Rule::range_any)))),
+    }
+  }
+
+  pub fn size(&self) -> u128 {
+    (u32::from(self.last) - u32::from(self.first)) as u128 + 1
+  }
+
+  pub fn contains(&self, ip: Ipv4Addr) -> bool {
+    self.first <= ip && ip <= self.last
+  }
+
+  pub fn details(&self) -> IpRange {
+    // the full range with every derived field, input is written in the notation
+    // of the kind, ex. 10.0.0.0+256 for a count
+    self.to_range(&self.notation())
+  }
+
+  fn notation(&self) -> String {
+    match self.kind {
+      RangeKind::Cidr { base, prefix } => format!("{base}/{prefix}"),
+      RangeKind::Hyphen { start, end } => format!("{start}-{end}"),
+      RangeKind::Wildcard { specified_octets: 0 } => "*".to_string(),
+      RangeKind::Wildcard { specified_octets } => {
+        let octets = self.first.octets().map(|o| o.to_string());
+        format!("{}.*", octets[..specified_octets as usize].join("."))
+      }
+      RangeKind::Count { start, count } => format!("{start}+{count}"),
+      RangeKind::Slice { from, to, start, end, cidr } => {
+        let bound = |b: Option<i64>| b.map(|b| b.to_string()).unwrap_or_default();
+        match cidr {
+          Some((base, prefix)) => format!("{base}/{prefix}[{}:{}]", bound(start), bound(end)),
+          None => format!("{from}-{to}[{}:{}]", bound(start), bound(end)),
+        }
+      }
+      RangeKind::Single { addr } => addr.to_string(),
+      // a bare address only reads back as classful under ParseOptions::classful
+      RangeKind::Classful { class, .. } => format!("{}/{}", self.first, class.default_prefix().unwrap_or(32)),
+    }
+  }
+
+  pub(crate) fn to_range(self, input: &str) -> IpRange {
+    IpRange::from_bounds(input, self.kind, u32::from(self.first), u32::from(self.last))
+  }
+
+  fn new(kind: RangeKind, first: u32, last: u32) -> Self {
+    CompactRange { first: Ipv4Addr::from(first), last: Ipv4Addr::from(last), kind }
+  }
+}
+
+/*
+single pass recognizer for every notation of range_any and range_pattern,
+returns None exactly when the grammar rejects the input
+
+semantic errors, such as a reversed hyphen range, are reported in the
+same order IpRange::parse checks them
+
+with an address mode, every address is read as by parse_ipv4_with
+instead, ex. 10.1/16 or 0x0a000001-0x0a0000ff. wildcards keep plain octets
+*/
+
+pub(crate) fn scan(input: &str) -> Option<Scanned> {
+  scan_with(input, None)
+}
+
+pub(crate) fn scan_with(input: &str, mode: Option<AddressMode>) -> Option<Scanned> {
+  let mut c = Cursor { input, pos: 0, mode, error: None };
+  let mut scanned = scan_cursor(&mut c)?;
+
+  // an ambiguous address wins over any later semantic error
+  if let Some(e) = c.error {
+    scanned.range = Err(e);
+  }
+  Some(scanned)
+}
+
+fn scan_cursor(c: &mut Cursor<'_>) -> Option<Scanned> {
+  let input = c.input;
+  let head = input.split(['/', '-', '+', '[']).next().unwrap_or_default();
+  let (parts, n) = if c.mode.is_some() && !head.contains('*') {
+    (c.address()?.to_be_bytes().map(Some), 4)
+  } else {
+    c.pattern()?
+  };
+
+  let fixed = parts[..n].iter().all(Option::is_some);
+  let ip = (n == 4 && fixed).then(|| to_u32(parts.map(|p| p.unwrap_or(0))));
+  let wildcard = parts[n - 1].is_none() && parts[..n - 1].iter().all(Option::is_some);
+
+  let (notation, syntax) = match (c.peek(), ip) {
+    (None, Some(a)) => (Notation::Single, Syntax::Single(a)),
+    (None, None) if wildcard => (Notation::Wildcard, Syntax::Wildcard(parts.map(|p| p.unwrap_or(0)), n as u8 - 1)),
+    (None, None) if n == 4 => (Notation::Wildcard, Syntax::Pattern(parts)),
+    (Some(b'/'), Some(a)) => {
+      c.pos += 1;
+      (Notation::Cidr, Syntax::Cidr(a, c.prefix()?))
+    }
+    (Some(b'-'), Some(a)) => {
+      c.pos += 1;
+      (Notation::Hyphen, Syntax::Hyphen(a, c.address()?))
+    }
+    (Some(b'+'), Some(a)) => {
+      c.pos += 1;
+      let count = c.digits()?;
+      let range = count_range(input, a, count);
+      return c.at_end().then_some(Scanned { notation: Notation::Count, base: None, cidr: None, range });
+    }
+    (Some(b'['), None) if wildcard => (Notation::Wildcard, Syntax::Wildcard(parts.map(|p| p.unwrap_or(0)), n as u8 - 1)),
+    _ => return None,
+  };
+
+  let cidr = match syntax {
+    Syntax::Cidr(a, prefix) => Some((Ipv4Addr::from(a), prefix)),
+    _ => None,
+  };
+  if c.at_end() {
+    return Some(Scanned { notation, base: None, cidr, range: evaluate(input, syntax) });
+  }
+
+  // anything left must be a slice of a cidr, hyphen or wildcard range
+  if matches!(syntax, Syntax::Single(_) | Syntax::Pattern(_)) || !c.eat(b'[') {
+    return None;
+  }
+  let start = c.index()?;
+  if !c.eat(b':') {
+    return None;
+  }
+  let end = c.index()?;
+  if !c.eat(b']') || !c.at_end() {
+    return None;
+  }
+
+  let range = evaluate(input, syntax).and_then(|base| slice_range(base, start, end));
+  Some(Scanned { notation: Notation::Slice, base: Some(notation), cidr, range })
+}
+
+fn evaluate(input: &str, syntax: Syntax) -> Result<CompactRange, IpRangeError> {
+  match syntax {
+    Syntax::Single(a) => Ok(CompactRange::new(RangeKind::Single { addr: Ipv4Addr::from(a) }, a, a)),
+    Syntax::Cidr(base, prefix) => {
+      let mask = mask_from_prefix(prefix);
+      let kind = RangeKind::Cidr { base: Ipv4Addr::from(base), prefix };
+      Ok(CompactRange::new(kind, base & mask, (base & mask) | !mask))
+    }
+    Syntax::Hyphen(a, b) if a > b => Err(IpRangeError::InvalidRangeOrder),
+    Syntax::Hyphen(a, b) => {
+      let kind = RangeKind::Hyphen { start: Ipv4Addr::from(a), end: Ipv4Addr::from(b) };
+      Ok(CompactRange::new(kind, a, b))
+    }
+    Syntax::Wildcard(octs, specified_octets) => Ok(wildcard_range(octs, specified_octets)),
+    Syntax::Pattern(parts) => {
+      // contiguous only when every wildcard is trailing
+      let specified = parts.iter().take_while(|p| p.is_some()).count();
+      if parts[specified..].iter().any(Option::is_some) {
+        return Err(IpRangeError::NonContiguous(input.to_string()));
+      }
+      Ok(wildcard_range(parts.map(|p| p.unwrap_or(0)), specified as u8))
+    }
+  }
+}
+
+fn wildcard_range(octs: [u8; 4], specified_octets: u8) -> CompactRange {
+  let mask = mask_from_prefix(8 * specified_octets);
+  let first = to_u32(octs) & mask;
+  CompactRange::new(RangeKind::Wildcard { specified_octets }, first, first | !mask)
+}
+
+fn count_range(input: &str, start: u32, count: &str) -> Result<CompactRange, IpRangeError> {
+  let count: u64 = count.parse().map_err(|_| IpRangeError::CountOverflow(input.to_string()))?;
+  if count == 0 {
+    return Err(IpRangeError::EmptyRange);
+  }
+
+  let end = start as u64 + (count - 1);
+  if end > u32::MAX as u64 {
+    return Err(IpRangeError::CountOverflow(input.to_string()));
+  }
+
+  let kind = RangeKind::Count { start: Ipv4Addr::from(start), count };
+  Ok(CompactRange::new(kind, start, end as u32))
+}
+
+fn slice_range(base: CompactRange, start: Option<i64>, end: Option<i64>) -> Result<CompactRange, IpRangeError> {
+  // python style slicing, out of bounds indices are clamped
+  let len = base.size() as i128;
+  let resolve = |idx: Option<i64>, default: i128| match idx {
+    Some(i) if i < 0 => (len + i as i128).max(0),
+    Some(i) => (i as i128).min(len),
+    None => default,
+  };
+  let lo = resolve(start, 0);
+  let hi = resolve(end, len);
+  if lo >= hi {
+    return Err(IpRangeError::EmptyRange);
+  }
+
+  let first = u32::from(base.first) + lo as u32;
+  let last = u32::from(base.first) + (hi - 1) as u32;
+  // a cidr base keeps its address as written, host bits included
+  let cidr = match base.kind {
+    RangeKind::Cidr { base, prefix } => Some((base, prefix)),
+    _ => None,
+  };
+  let kind = RangeKind::Slice { from: base.first, to: base.last, start, end, cidr };
+  Ok(CompactRange::new(kind, first, last))
+}
+
+fn to_u32(octs: [u8; 4]) -> u32 {
+  u32::from_be_bytes(octs)
+}
+
+
+// ===== scanner =====
+
+
+impl<'a> Cursor<'a> {
+  fn peek(&self) -> Option<u8> {
+    self.input.as_bytes().get(self.pos).copied()
+  }
+
+  fn eat(&mut self, b: u8) -> bool {
+    if self.peek() == Some(b) {
+      self.pos += 1;
+      return true;
+    }
+    false
+  }
+
+  fn at_end(&self) -> bool {
+    self.pos == self.input.len()
+  }
+
+  fn digits(&mut self) -> Option<&'a str> {
+    // a run of digits without leading zeros, as count and octet in the grammar
+    let start = self.pos;
+    while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+      self.pos += 1;
+    }
+
+    let d = &self.input[start..self.pos];
+    if d.is_empty() || (d.len() > 1 && d.starts_with('0')) {
+      return None;
+    }
+    Some(d)
+  }
+
+  fn number(&mut self, max: u32) -> Option<u32> {
+    let d = self.digits()?;
+    if d.len() > 3 {
+      return None;
+    }
+    d.parse().ok().filter(|&v| v <= max)
+  }
+
+  fn prefix(&mut self) -> Option<u8> {
+    self.number(32).map(|p| p as u8)
+  }
+
+  fn octet(&mut self) -> Option<u8> {
+    self.number(255).map(|o| o as u8)
+  }
+
+  fn part(&mut self) -> Option<Option<u8>> {
+    // an octet or a wildcard
+    if self.eat(b'*') {
+      return Some(None);
+    }
+    self.octet().map(Some)
+  }
+
+  fn pattern(&mut self) -> Option<([Option<u8>; 4], usize)> {
+    // up to four dot separated parts, stops after a wildcard that isn't followed by a dot
+    let mut parts = [None; 4];
+    parts[0] = self.part()?;
+    let mut n = 1;
+    while n < 4 && self.eat(b'.') {
+      parts[n] = self.part()?;
+      n += 1;
+    }
+    Some((parts, n))
+  }
+
+  fn ip(&mut self) -> Option<u32> {
+    let mut octs = [0u8; 4];
+    for (i, o) in octs.iter_mut().enumerate() {
+      if i > 0 && !self.eat(b'.') {
+        return None;
+      }
+      *o = self.octet()?;
+    }
+    Some(to_u32(octs))
+  }
+
+  fn address(&mut self) -> Option<u32> {
+    // a whole address in the cursor's mode
+    let Some(mode) = self.mode else {
+      return self.ip();
+    };
+
+    let token = address_token(&self.input[self.pos..]);
+    let (addr, form) = parse_ipv4_with(token, AddressMode::Lenient).ok()?;
+    if mode == AddressMode::Strict && form != AddressForm::DottedQuad && self.error.is_none() {
+      self.error = Some(IpRangeError::AmbiguousAddress { input: token.to_string(), form });
+    }
+    self.pos += token.len();
+    Some(u32::from(addr))
+  }
+
+  fn index(&mut self) -> Option<Option<i64>> {
+    // optional python style index, huge values saturate as in parse_slice_index
+    let negative = match self.peek() {
+      Some(b'-') => {
+        self.pos += 1;
+        true
+      }
+      Some(b) if b.is_ascii_digit() => false,
+      _ => return Some(None),
+    };
+
+    let d = self.digits()?;
+    let value = d.parse::<i64>().map(|v| if negative { -v } else { v });
+    Some(Some(value.unwrap_or(if negative { i64::MIN } else { i64::MAX })))
+  }
+}
+
+
+// ===== type conversions =====
+
+
+impl From<&IpRange> for CompactRange {
+  fn from(r: &IpRange) -> Self {
+    CompactRange { first: r.first, last: r.last, kind: r.kind }
+  }
+}
+
+impl From<CompactRange> for IpRange {
+  fn from(r: CompactRange) -> Self {
+    r.details()
+  }
+}
+
+impl PartialEq for CompactRange {
+  fn eq(&self, other: &Self) -> bool {
+    (self.first, self.last) == (other.first, other.last)
+  }
+}
+
+impl Eq for CompactRange {}
+
+impl Hash for CompactRange {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    (self.first, self.last).hash(state);
+  }
+}
+
+impl PartialOrd for CompactRange {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for CompactRange {
+  fn cmp(&self, other: &Self) -> Ordering {
+    (self.first, self.last).cmp(&(other.first, other.last))
+  }
+}
+
+impl fmt::Display for CompactRange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // same canonical form as IpRange
+    match infer_cidr_from_range(u32::from(self.first), u32::from(self.last)) {
+      Some(prefix) => write!(f, "{}/{}", self.first, prefix),
+      None => write!(f, "{}-{}", self.first, self.last),
+    }
+  }
+}
+
+impl std::str::FromStr for CompactRange {
+  type Err = IpRangeError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    CompactRange::parse(s)
+  }
+}
@@ -0,0 +1,303 @@
+use pest::error::{ErrorVariant, InputLocation};
+use pest::Parser;
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::{IpParser, Rule};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticCode {
+  EmptyInput, // nothing to parse
+  UnexpectedWhitespace, // whitespace around or inside a range
+  InvalidCharacter, // a character no notation uses
+  EmptyOctet, // two dots in a row, ex. 10..0.1
+  OctetOutOfRange, // octet above 255
+  LeadingZero, // zero padded number, ex. 010
+  MissingOctets, // fewer than 4 octets, ex. 10.0.1
+  TooManyOctets, // more than 4 octets, ex. 10.0.0.0.1
+  MissingPrefix, // nothing after the slash
+  PrefixOutOfRange, // prefix above 32
+  MissingCount, // nothing after the plus
+  UnexpectedToken // anything else, located by the grammar
+}
+
+// a parse failure located in the input, with a hint on how to fix it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+  pub input: String, // the input that failed to parse
+  pub span: Range<usize>, // byte span of the offending part
+  pub code: DiagnosticCode,
+  pub message: String, // what went wrong, ex. "octet out of range"
+  pub hint: Option<String>, // how to fix it, ex. "octet 256 exceeds 255"
+}
+
+
+// ===== core logic =====
+
+
+impl DiagnosticCode {
+  pub fn as_str(self) -> &'static str {
+    // stable machine readable name
+    match self {
+      DiagnosticCode::EmptyInput => "empty-input",
+      DiagnosticCode::UnexpectedWhitespace => "unexpected-whitespace",
+      DiagnosticCode::InvalidCharacter => "invalid-character",
+      DiagnosticCode::EmptyOctet => "empty-octet",
+      DiagnosticCode::OctetOutOfRange => "octet-out-of-range",
+      DiagnosticCode::LeadingZero => "leading-zero",
+      DiagnosticCode::MissingOctets => "missing-octets",
+      DiagnosticCode::TooManyOctets => "too-many-octets",
+      DiagnosticCode::MissingPrefix => "missing-prefix",
+      DiagnosticCode::PrefixOutOfRange => "prefix-out-of-range",
+      DiagnosticCode::MissingCount => "missing-count",
+      DiagnosticCode::UnexpectedToken => "unexpected-token",
+    }
+  }
+}
+
+impl Diagnostic {
+  fn new(input: &str, span: Range<usize>, code: DiagnosticCode, message: &str, hint: Option<String>) -> Self {
+    Diagnostic { input: input.to_string(), span, code, message: message.to_string(), hint }
+  }
+
+  /*
+  render the diagnostic with a caret under the offending part
+
+  ex.
+    error[octet-out-of-range]: octet out of range
+      192.168.0.256/24
+                ^^^
+      hint: octet 256 exceeds 255
+  */
+
+  pub fn render(&self) -> String {
+    let pad = self.input[..self.span.start].chars().count();
+    let width = self.input[self.span.clone()].chars().count().max(1);
+
+    let mut out = format!("error[{}]: {}\n", self.code.as_str(), self.message);
+    out.push_str(&format!("  {}\n", self.input));
+    out.push_str(&format!("  {}{}\n", " ".repeat(pad), "^".repeat(width)));
+    if let Some(hint) = &self.hint {
+      out.push_str(&format!("  hint: {hint}\n"));
+    }
+    out
+  }
+}
+
+pub(crate) fn diagnose(input: &str, rule: Rule) -> Diagnostic {
+  // explain why input doesn't match rule, preferring the hand written checks
+  analyze(input).err().unwrap_or_else(|| from_grammar(input, rule))
+}
+
+pub(crate) fn from_grammar(input: &str, rule: Rule) -> Diagnostic {
+  // locate the failure with the grammar, for anything the checks don't cover
+  let err = match IpParser::parse(rule, input) {
+    Err(err) => err,
+    Ok(pairs) => {
+      // the rule matched a prefix only, point at the leftover input
+      let end = pairs.last().map(|p| p.as_span().end()).unwrap_or(0);
+      let c = input[end..].chars().next().map(|c| c.len_utf8()).unwrap_or(0);
+      return Diagnostic::new(input, end..end + c, DiagnosticCode::UnexpectedToken, "unexpected trailing input", None);
+    }
+  };
+
+  let start = match err.location {
+    InputLocation::Pos(p) => p,
+    InputLocation::Span((a, _)) => a,
+  };
+  let c = input[start..].chars().next().map(|c| c.len_utf8()).unwrap_or(0);
+
+  let hint = match &err.variant {
+    ErrorVariant::ParsingError { positives, .. } if !positives.is_empty() => {
+      let mut expected: Vec<&str> = positives.iter().map(|r| rule_label(*r)).collect();
+      expected.dedup();
+      Some(format!("expected {}", expected.join(" or ")))
+    }
+    _ => None,
+  };
+
+  let message = if start >= input.len() { "unexpected end of input" } else { "unexpected input" };
+  Diagnostic::new(input, start..start + c, DiagnosticCode::UnexpectedToken, message, hint)
+}
+
+fn rule_label(rule: Rule) -> &'static str {
+  // human readable name of a grammar rule
+  match rule {
+    Rule::octet | Rule::pattern_octet | Rule::brace_octet => "an octet",
+    Rule::cidr_prefix => "a prefix length",
+    Rule::ip | Rule::range_single => "an address",
+    Rule::count => "a count",
+    Rule::slice_index => "an index",
+    Rule::brace | Rule::brace_item | Rule::brace_value => "a brace list",
+    Rule::alias_name => "an alias name",
+    Rule::set_union | Rule::set_intersection | Rule::set_difference
+      | Rule::set_symmetric_difference | Rule::set_complement => "an operator",
+    Rule::EOI => "end of input",
+    _ => "a range",
+  }
+}
+
+
+// ===== hand written checks =====
+
+
+fn analyze(input: &str) -> Result<(), Diagnostic> {
+  // walk the common notations and report the first problem found
+  use DiagnosticCode::*;
+
+  if input.trim().is_empty() {
+    let hint = Some("expected a range, ex. 192.168.0.0/24".to_string());
+    return Err(Diagnostic::new(input, 0..input.len(), EmptyInput, "empty input", hint));
+  }
+
+  let lead = input.len() - input.trim_start().len();
+  let trail = input.trim_end().len();
+  if lead > 0 || trail < input.len() {
+    let span = if lead > 0 { 0..lead } else { trail..input.len() };
+    let hint = Some("remove the surrounding whitespace".to_string());
+    return Err(Diagnostic::new(input, span, UnexpectedWhitespace, "unexpected whitespace", hint));
+  }
+
+  let mut pos = check_address(input, 0)?;
+  let b = input.as_bytes();
+  if pos >= b.len() {
+    return Ok(());
+  }
+
+  match b[pos] {
+    b'/' => {
+      let (end, digits) = digit_run(input, pos + 1);
+      if digits.is_empty() {
+        let hint = Some("expected a prefix length after '/', ex. /24".to_string());
+        return Err(Diagnostic::new(input, pos..pos + 1, MissingPrefix, "missing prefix", hint));
+      }
+      check_leading_zero(input, pos + 1, digits)?;
+      if digits.len() > 2 || digits.parse::<u8>().unwrap() > 32 {
+        let hint = Some(format!("prefix must be 0–32, found {digits}"));
+        return Err(Diagnostic::new(input, pos + 1..end, PrefixOutOfRange, "prefix out of range", hint));
+      }
+      pos = end;
+    }
+    b'-' => pos = check_address(input, pos + 1)?,
+    b'+' => {
+      let (end, digits) = digit_run(input, pos + 1);
+      if digits.is_empty() {
+        let hint = Some("expected a number of addresses after '+', ex. +256".to_string());
+        return Err(Diagnostic::new(input, pos..pos + 1, MissingCount, "missing count", hint));
+      }
+      check_leading_zero(input, pos + 1, digits)?;
+      pos = end;
+    }
+    _ => {}
+  }
+
+  // slices are left to the grammar
+  match input[pos..].chars().next() {
+    None | Some('[') => Ok(()),
+    Some(c) => Err(unexpected_char(input, pos, c)),
+  }
+}
+
+fn check_address(input: &str, start: usize) -> Result<usize, Diagnostic> {
+  // check an address or wildcard at start, returns where it ends
+  use DiagnosticCode::*;
+
+  let b = input.as_bytes();
+  let mut pos = start;
+  let mut parts = 0;
+  let mut last_wildcard;
+
+  loop {
+    if pos < b.len() && b[pos] == b'*' {
+      pos += 1;
+      last_wildcard = true;
+    } else {
+      let (end, digits) = digit_run(input, pos);
+      if digits.is_empty() {
+        return Err(match input[pos..].chars().next() {
+          Some(c) if c != '.' && c != '/' && c != '-' && c != '+' && c != '[' => unexpected_char(input, pos, c),
+          _ => {
+            let hint = Some("expected a number from 0 to 255".to_string());
+            let span = pos..(pos + 1).min(input.len());
+            Diagnostic::new(input, span, EmptyOctet, "missing octet", hint)
+          }
+        });
+      }
+
+      check_leading_zero(input, pos, digits)?;
+      if digits.len() > 3 || digits.parse::<u16>().unwrap() > 255 {
+        let hint = Some(format!("octet {digits} exceeds 255"));
+        return Err(Diagnostic::new(input, pos..end, OctetOutOfRange, "octet out of range", hint));
+      }
+      pos = end;
+      last_wildcard = false;
+    }
+
+    parts += 1;
+    if parts > 4 {
+      let end = pos + input[pos..].find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '*')).unwrap_or(input.len() - pos);
+      let hint = Some("an IPv4 address has 4 octets".to_string());
+      return Err(Diagnostic::new(input, start..end, TooManyOctets, "too many octets", hint));
+    }
+
+    if pos < b.len() && b[pos] == b'.' {
+      pos += 1;
+    } else {
+      break;
+    }
+  }
+
+  // a trailing wildcard stands in for every missing octet
+  if parts < 4 && !last_wildcard {
+    let hint = Some(format!("an IPv4 address has 4 octets, found {parts}"));
+    return Err(Diagnostic::new(input, start..pos, MissingOctets, "missing octets", hint));
+  }
+
+  Ok(pos)
+}
+
+fn check_leading_zero(input: &str, start: usize, digits: &str) -> Result<(), Diagnostic> {
+  if digits.len() > 1 && digits.starts_with('0') {
+    let trimmed = digits.trim_start_matches('0');
+    let trimmed = if trimmed.is_empty() { "0" } else { trimmed };
+    let hint = Some(format!("write {trimmed} instead of {digits}"));
+    return Err(Diagnostic::new(input, start..start + digits.len(), DiagnosticCode::LeadingZero, "leading zero", hint));
+  }
+  Ok(())
+}
+
+fn digit_run(input: &str, start: usize) -> (usize, &str) {
+  // the ascii digits starting at start, and where they end
+  let len = input[start..].find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len() - start);
+  (start + len, &input[start..start + len])
+}
+
+fn unexpected_char(input: &str, pos: usize, c: char) -> Diagnostic {
+  if c.is_whitespace() {
+    let hint = Some("ranges can't contain whitespace".to_string());
+    return Diagnostic::new(input, pos..pos + c.len_utf8(), DiagnosticCode::UnexpectedWhitespace, "unexpected whitespace", hint);
+  }
+
+  let hint = Some(format!("unexpected {c:?}"));
+  Diagnostic::new(input, pos..pos + c.len_utf8(), DiagnosticCode::InvalidCharacter, "invalid character", hint)
+}
+
+
+// ===== type conversions =====
+
+
+impl fmt::Display for DiagnosticCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.hint {
+      Some(hint) => write!(f, "{}: {}", self.message, hint),
+      None => f.write_str(&self.message),
+    }
+  }
+}
@@ -0,0 +1,145 @@
+use pest::iterators::Pairs;
+use pest::pratt_parser::{Assoc, Op, PrattParser};
+use pest::Parser;
+
+use std::sync::LazyLock;
+
+use crate::diagnostic::from_grammar;
+use crate::inet_aton::normalize_addresses;
+use crate::options::strip_zero_padding;
+use crate::set::Operand;
+use crate::{Aliases, IpParser, IpRangeError, IpRangeSet, ItemWarning, ParseOptions, Rule};
+
+// result of evaluating an expression, with the warnings of its operands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluated {
+  pub set: IpRangeSet,
+  pub warnings: Vec<ItemWarning>,
+}
+
+// operator precedence, loosest first
+static PRATT: LazyLock<PrattParser<Rule>> = LazyLock::new(|| {
+  PrattParser::new()
+    .op(Op::infix(Rule::set_union, Assoc::Left))
+    .op(Op::infix(Rule::set_symmetric_difference, Assoc::Left))
+    .op(Op::infix(Rule::set_difference, Assoc::Left))
+    .op(Op::infix(Rule::set_intersection, Assoc::Left))
+    .op(Op::prefix(Rule::set_complement))
+});
+
+
+// ===== core logic =====
+
+
+impl IpRangeSet {
+
+  /*
+  evaluate a set expression over ranges
+
+  operators, loosest binding first:
+  - "a | b": union
+  - "a ^ b": symmetric difference
+  - "a - b": difference
+  - "a & b": intersection
+  - "~a": complement
+  parentheses group, and operands may use any notation IpRange::parse supports,
+  ex. "(10.0.0.0/8 - 10.1.0.0/16) | 192.168.*". sparse wildcard patterns
+  stand for every address they match, as in IpRangeSet::parse, ex. "10.*.0.1"
+
+  "a.b.c.d-e.f.g.h" without spaces is always a hyphen range,
+  write "a.b.c.d - e.f.g.h" to subtract two addresses
+  */
+
+  pub fn eval(expr: &str) -> Result<IpRangeSet, IpRangeError> {
+    Self::eval_program(expr, None, &ParseOptions::default()).map(|e| e.set)
+  }
+
+  pub fn eval_with(expr: &str, opts: &ParseOptions) -> Result<IpRangeSet, IpRangeError> {
+    // same as eval, but operands are parsed as in IpRange::parse_with
+    Self::eval_program(expr, None, opts).map(|e| e.set)
+  }
+
+  pub fn eval_with_aliases(expr: &str, aliases: &Aliases) -> Result<IpRangeSet, IpRangeError> {
+    // same as eval, but operands may also name an alias, ex. "trusted - vpn".
+    // operands are parsed with the options of the aliases, see Aliases::with_options
+    Self::eval_program(expr, Some(aliases), aliases.options()).map(|e| e.set)
+  }
+
+  pub fn eval_with_warnings(expr: &str, opts: &ParseOptions) -> Result<Evaluated, IpRangeError> {
+    // same as eval_with, but also reports suspicious operands as IpRange::parse_with_warnings does.
+    // offsets point into the expression as evaluated, after zero padding and legacy addresses are rewritten
+    Self::eval_program(expr, None, opts)
+  }
+
+  pub fn eval_with_aliases_and_warnings(expr: &str, aliases: &Aliases) -> Result<Evaluated, IpRangeError> {
+    // same as eval_with_warnings, for eval_with_aliases. the definitions of the aliases are not checked
+    Self::eval_program(expr, Some(aliases), aliases.options())
+  }
+
+  fn eval_program(expr: &str, aliases: Option<&Aliases>, opts: &ParseOptions) -> Result<Evaluated, IpRangeError> {
+    // operands have to match the grammar, so zero padding and legacy
+    // addresses are rewritten before parsing
+    let padded;
+    let expr = if opts.zero_padded {
+      padded = strip_zero_padding(expr);
+      padded.as_str()
+    } else {
+      expr
+    };
+    let normalized;
+    let expr = match opts.address_mode {
+      Some(mode) => {
+        normalized = normalize_addresses(expr, mode)?;
+        normalized.as_str()
+      }
+      None => expr,
+    };
+
+    let mut pairs = IpParser::parse(Rule::set_program, expr)
+      .map_err(|_| IpRangeError::Syntax(Box::new(from_grammar(expr, Rule::set_program))))?;
+    let body = pairs.next().expect("validated by grammar").into_inner();
+    let mut warnings = Vec::new();
+    let set = eval_pairs(body, aliases, opts, &mut warnings)?;
+    Ok(Evaluated { set, warnings })
+  }
+}
+
+fn eval_pairs(
+  pairs: Pairs<'_, Rule>,
+  aliases: Option<&Aliases>,
+  opts: &ParseOptions,
+  warnings: &mut Vec<ItemWarning>,
+) -> Result<IpRangeSet, IpRangeError> {
+  PRATT
+    .map_primary(|p| match p.as_rule() {
+      Rule::range_any => IpRangeSet::parse_operand(p.as_str(), opts).map(|operand| match operand {
+        Operand::Range(r) => {
+          let (item, offset) = (p.as_str(), p.as_span().start());
+          warnings.extend(r.warnings().into_iter().map(|warning| ItemWarning { item: item.to_string(), offset, warning }));
+          IpRangeSet::from(r)
+        }
+        Operand::Pattern(set) => set,
+      }),
+      Rule::alias_name => match aliases {
+        Some(aliases) => aliases.resolve(p.as_str()),
+        None => Err(IpRangeError::UndefinedAlias(p.as_str().to_string())),
+      },
+      Rule::set_expr => eval_pairs(p.into_inner(), aliases, opts, warnings),
+      r => unreachable!("unexpected primary {r:?}"),
+    })
+    .map_prefix(|op, rhs| match op.as_rule() {
+      Rule::set_complement => Ok(rhs?.complement()),
+      r => unreachable!("unexpected prefix {r:?}"),
+    })
+    .map_infix(|lhs, op, rhs| {
+      let (lhs, rhs) = (lhs?, rhs?);
+      Ok(match op.as_rule() {
+        Rule::set_union => lhs.union(&rhs),
+        Rule::set_intersection => lhs.intersection(&rhs),
+        Rule::set_difference => lhs.difference(&rhs),
+        Rule::set_symmetric_difference => lhs.symmetric_difference(&rhs),
+        r => unreachable!("unexpected infix {r:?}"),
+      })
+    })
+    .parse(pairs)
+}
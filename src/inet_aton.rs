@@ -0,0 +1,133 @@
+use std::net::Ipv4Addr;
+
+use crate::IpRangeError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressMode {
+  Strict, // only plain dotted quads, legacy forms are reported as ambiguous
+  Lenient // every form accepted by libc's inet_aton
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressForm {
+  DottedQuad, // a.b.c.d in plain decimal, ex. 127.0.0.1
+  Legacy { parts: u8, octal: bool, hex: bool } // ex. 0x7f.1, 017700000001, 2130706433, 10.1
+}
+
+
+// ===== address parsing =====
+
+
+/*
+parse an IPv4 address and report which form it was written in
+
+inet_aton accepts 1 to 4 dot separated parts, the last part filling all
+remaining bytes (a, a.b, a.b.c, a.b.c.d). each part may be decimal,
+octal with a leading "0", or hex with a leading "0x".
+
+in strict mode those legacy forms are rejected with
+IpRangeError::AmbiguousAddress, since "010.0.0.1" means 8.0.0.1 to libc
+but 10.0.0.1 to most people reading it
+*/
+
+pub fn parse_ipv4_with(s: &str, mode: AddressMode) -> Result<(Ipv4Addr, AddressForm), IpRangeError> {
+  let (addr, form) = parse_inet_aton(s).ok_or_else(|| IpRangeError::InvalidIp(s.to_string()))?;
+
+  if mode == AddressMode::Strict && form != AddressForm::DottedQuad {
+    return Err(IpRangeError::AmbiguousAddress { input: s.to_string(), form });
+  }
+
+  Ok((Ipv4Addr::from(addr), form))
+}
+
+pub(crate) fn address_token(s: &str) -> &str {
+  // the longest prefix of s that may be an inet_aton address, ex. "0x7f.1" of "0x7f.1/8"
+  let end = s.find(|c: char| !(c.is_ascii_hexdigit() || c == 'x' || c == 'X' || c == '.')).unwrap_or(s.len());
+  &s[..end]
+}
+
+pub(crate) fn normalize_addresses(text: &str, mode: AddressMode) -> Result<String, IpRangeError> {
+  // rewrite every address of an expression as a dotted quad, ex. "10.1 | 0x0b000000/8".
+  // numbers after "/", "+", "." or inside a slice are prefixes, counts and octets
+  let mut out = String::with_capacity(text.len());
+  let mut prev: Option<char> = None;
+  let mut in_slice = false;
+  let mut rest = text;
+
+  while let Some(c) = rest.chars().next() {
+    let starts_token = !in_slice && !prev.is_some_and(|p| p.is_ascii_alphanumeric() || "_/+.*:".contains(p));
+    let token = if starts_token { address_token(rest) } else { "" };
+
+    if token.is_empty() {
+      in_slice = match c {
+        '[' => true,
+        ']' => false,
+        _ => in_slice,
+      };
+      out.push(c);
+      prev = Some(c);
+      rest = &rest[c.len_utf8()..];
+      continue;
+    }
+
+    match parse_ipv4_with(token, mode) {
+      Ok((addr, _)) => out.push_str(&addr.to_string()),
+      Err(e @ IpRangeError::AmbiguousAddress { .. }) => return Err(e),
+      Err(_) => out.push_str(token), // not an address, ex. the "10." of "10.*"
+    }
+    prev = token.chars().last();
+    rest = &rest[token.len()..];
+  }
+
+  Ok(out)
+}
+
+fn parse_inet_aton(s: &str) -> Option<(u32, AddressForm)> {
+  let mut vals = [0u64; 4];
+  let mut parts = 0usize;
+  let mut octal = false;
+  let mut hex = false;
+
+  for p in s.split('.') {
+    if parts == 4 {
+      return None;
+    }
+
+    let (digits, radix) = if let Some(h) = p.strip_prefix("0x").or_else(|| p.strip_prefix("0X")) {
+      hex = true;
+      (h, 16)
+    } else if p.len() > 1 && p.starts_with('0') {
+      octal = true;
+      (&p[1..], 8)
+    } else {
+      (p, 10)
+    };
+
+    // from_str_radix would also accept a leading sign
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+      return None;
+    }
+
+    vals[parts] = u64::from_str_radix(digits, radix).ok()?;
+    parts += 1;
+  }
+
+  // every part but the last is a single byte, the last fills the rest
+  let last_bits = 8 * (5 - parts as u32);
+  if vals[..parts - 1].iter().any(|&v| v > 255) || vals[parts - 1] >= 1u64 << last_bits {
+    return None;
+  }
+
+  let mut acc: u64 = vals[parts - 1];
+  for (i, &v) in vals[..parts - 1].iter().enumerate() {
+    acc |= v << (8 * (3 - i as u32));
+  }
+
+  let form = if parts == 4 && !octal && !hex {
+    AddressForm::DottedQuad
+  } else {
+    AddressForm::Legacy { parts: parts as u8, octal, hex }
+  };
+
+  Some((acc as u32, form))
+}
@@ -2,41 +2,145 @@ use pest::Parser;
 use pest_derive::Parser;
 use thiserror::Error;
 
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::net::Ipv4Addr;
+use std::ops::RangeInclusive;
 use std::str::FromStr;
+
+use compact::scan_with;
+use diagnostic::diagnose;
 use std::iter::FusedIterator;
 
+mod alias;
+#[cfg(feature = "bitmap")]
+mod bitmap;
+mod brace;
+mod bulk;
+mod chunks;
+mod compact;
+mod diagnostic;
+mod expr;
+mod inet_aton;
+mod list;
+mod options;
+#[cfg(feature = "rayon")]
+mod par;
+mod pattern;
+mod sample;
+mod set;
+mod shard;
+mod shuffle;
+mod warning;
+
+pub use alias::Aliases;
+#[cfg(feature = "bitmap")]
+pub use bitmap::AddressBitmap;
+pub use brace::BraceExpansion;
+pub use bulk::{parse_bulk, parse_bulk_reader, stream_bulk, BulkEntry, BulkStream};
+pub use chunks::{Blocks, Chunks, StepIter};
+pub use compact::CompactRange;
+pub use diagnostic::{Diagnostic, DiagnosticCode};
+pub use expr::Evaluated;
+pub use inet_aton::{parse_ipv4_with, AddressForm, AddressMode};
+pub use list::{ListEntry, ListError, ListWarning, RangeList};
+pub use options::{HostBits, Notation, ParseOptions};
+#[cfg(feature = "rayon")]
+pub use par::{ParIpRangeIter, ParIpRangeSetIter};
+pub use pattern::{IpPattern, IpPatternIter, WildcardRange};
+pub use set::{IpRangeSet, ItemError, ItemWarning, ParsedSet};
+pub use shard::{Shard, ShardStrategy};
+pub use shuffle::ShuffledIter;
+pub use warning::{ParseWarning, Parsed};
+
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 struct IpParser;
 
 #[derive(Debug, Error)]
 pub enum IpRangeError {
-  #[error(transparent)]
-  Pest(#[from] pest::error::Error<Rule>),
+  #[error("{0}")]
+  Syntax(Box<Diagnostic>),
 
   #[error("invalid IPv4 address: {0}")]
   InvalidIp(String),
 
+  #[error("ambiguous IPv4 address in legacy inet_aton form: {input}")]
+  AmbiguousAddress { input: String, form: AddressForm },
+
+  #[error("host bits set in {input}, the network is {network}/{prefix}")]
+  HostBitsSet { input: String, network: Ipv4Addr, prefix: u8 },
+
+  #[error("{0} notation is disabled")]
+  NotationDisabled(Notation),
+
+  #[error("invalid prefix: /{0} exceeds /32")]
+  InvalidPrefix(u8),
+
+  #[error("invalid shard {index}/{count}: expected an index below the shard count")]
+  InvalidShard { index: u64, count: u64 },
+
   #[error("invalid range: start > end")]
   InvalidRangeOrder,
 
+  #[error("invalid range: count overflows 255.255.255.255: {0}")]
+  CountOverflow(String),
+
+  #[error("invalid range: range is empty")]
+  EmptyRange,
+
+  #[error("invalid brace expansion: {0}")]
+  InvalidBrace(String),
+
+  #[error("range is not contiguous: {0}")]
+  NonContiguous(String),
+
+  #[error("undefined alias: {0}")]
+  UndefinedAlias(String),
+
+  #[error("alias cycle: {}", .0.join(" -> "))]
+  AliasCycle(Vec<String>),
+
+  #[error("invalid alias definition on line {line}: {message}")]
+  AliasSyntax { line: usize, message: String },
+
+  #[error("in alias {name}: {source}")]
+  InAlias { name: String, source: Box<IpRangeError> },
+
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+
   #[error("unsupported format")]
   UnsupportedFormat
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressClass {
+  A, // 0.0.0.0 - 127.255.255.255, default /8
+  B, // 128.0.0.0 - 191.255.255.255, default /16
+  C, // 192.0.0.0 - 223.255.255.255, default /24
+  D, // 224.0.0.0 - 239.255.255.255, multicast
+  E  // 240.0.0.0 - 255.255.255.255, reserved
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RangeKind {
   Cidr { base: Ipv4Addr, prefix: u8 },
   Hyphen { start: Ipv4Addr, end: Ipv4Addr },
-  Wildcard { specified_octets: u8 }
+  Wildcard { specified_octets: u8 },
+  Count { start: Ipv4Addr, count: u64 },
+  Slice { from: Ipv4Addr, to: Ipv4Addr, start: Option<i64>, end: Option<i64>, cidr: Option<(Ipv4Addr, u8)> }, // cidr base as written, if any
+  Single { addr: Ipv4Addr },
+  Classful { base: Ipv4Addr, class: AddressClass }
 }
 
 
 // ===== core logic =====
 
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+// equality, ordering and hashing only look at the covered interval,
+// so 10.0.0.0/24 == 10.0.0.0-10.0.0.255
+#[derive(Debug, Clone)]
 pub struct IpRange {
   pub input: String, // original input
   pub kind: RangeKind, // kind of input that was parsed
@@ -70,63 +174,74 @@ impl IpRange {
   - cidr: "a.b.c.d/nn"
   - hyphen: "a.b.c.d-e.f.g.h"
   - wildcard: "*", "a.*", "a.b.*", "a.b.c.*"
+  - count: "a.b.c.d+n"
+  - slice: any of the above followed by "[start:end]"
+  - single: "a.b.c.d"
+  - pattern: "a.b.*.*", as long as every wildcard is trailing
   */
 
   pub fn parse(input: &str) -> Result<Self, IpRangeError> {
-    // input classification
-    if full_match(Rule::range_cidr, input) {
-      Self::parse_cidr(input)
+    Self::parse_with(input, &ParseOptions::default())
+  }
 
-    } else if full_match(Rule::range_hyphen, input) {
-      Self::parse_hyphen(input)
+  pub fn parse_with(input: &str, opts: &ParseOptions) -> Result<Self, IpRangeError> {
+    // parse under a custom policy, input keeps the text as given.
+    // masked host bits are not reported here, use parse_with_warnings for that
+    let mut r = Self::parse_notation(&opts.normalize(input), opts)?;
+    r.input = input.to_string();
+    Ok(r)
+  }
 
-    } else if full_match(Rule::range_wildcard, input) {
-      Self::parse_wildcard(input)
+  pub fn parse_classful(input: &str) -> Result<Self, IpRangeError> {
+    // same as parse, but a bare address infers its legacy classful prefix,
+    // ex. 10.0.0.0 -> 10.0.0.0/8, 172.16.0.0 -> 172.16.0.0/16
+    Self::parse_with(input, &ParseOptions::new().classful(true))
+  }
 
-    } else {
-      IpParser::parse(Rule::range_any, input)?;
-      Err(IpRangeError::UnsupportedFormat)
+  fn parse_notation(input: &str, opts: &ParseOptions) -> Result<Self, IpRangeError> {
+    // single pass scan, the grammar is only consulted to explain a rejection
+    let Some(scanned) = scan_with(input, opts.address_mode) else {
+      return Err(IpRangeError::Syntax(Box::new(diagnose(input, Rule::range_any))));
+    };
+
+    for notation in [Some(scanned.notation), scanned.base].into_iter().flatten() {
+      if !opts.allows(notation) {
+        return Err(IpRangeError::NotationDisabled(notation));
+      }
+    }
+
+    if let Some((base, prefix)) = scanned.cidr && opts.host_bits == HostBits::Reject {
+      let network = Ipv4Addr::from(u32::from(base) & mask_from_prefix(prefix));
+      if network != base {
+        let text = input.split_once('[').map_or(input, |(base_s, _)| base_s);
+        return Err(IpRangeError::HostBitsSet { input: text.to_string(), network, prefix });
+      }
+    }
+
+    let range = scanned.range?;
+    match range.kind {
+      RangeKind::Single { addr } if opts.classful => Ok(Self::classful_single(input, addr)),
+      _ => Ok(range.to_range(input)),
     }
   }
 
-  fn parse_cidr(input: &str) -> Result<Self, IpRangeError> {
-    // parse ip address followed by a cidr prefix, ex. 192.168.0.1/24
-    let (ip_s, prefix_s) = input.split_once('/').expect("validated by grammar");
-    let base = parse_ipv4(ip_s)?;
-    let prefix: u8 = prefix_s.parse().unwrap();
+  fn classful_single(input: &str, addr: Ipv4Addr) -> Self {
+    // a single bare address with its classful default prefix.
+    // class d and e have no default prefix and stay a single host
+    let base = u32::from(addr);
+    let class = AddressClass::of(addr);
+    let prefix = class.default_prefix().unwrap_or(32);
 
     let mask = mask_from_prefix(prefix);
-    let hostmask = !mask;
     let network = base & mask;
-    let broadcast = network | hostmask;
+    let broadcast = network | !mask;
 
-    let first = Ipv4Addr::from(network);
-    let last = Ipv4Addr::from(broadcast);
-
-    Ok(IpRange {
-      input: input.to_string(),
-      kind: RangeKind::Cidr { base: Ipv4Addr::from(base), prefix },
-      first,
-      last,
-      network: Some(first),
-      broadcast: Some(last),
-      prefix: Some(prefix),
-      netmask: Some(Ipv4Addr::from(mask)),
-      hostmask: Some(Ipv4Addr::from(hostmask)),
-      size: size_from_prefix(prefix),
-    })
+    let kind = RangeKind::Classful { base: addr, class };
+    Self::from_bounds(input, kind, network, broadcast)
   }
 
-  fn parse_hyphen(input: &str) -> Result<Self, IpRangeError> {
-    // parse two ip addresses joined by a hyphen, ex. 192.168.0.0-192.168.0.100
-    let (start_s, end_s) = input.split_once('-').expect("validated by grammar");
-    
-    let start_val = parse_ipv4(start_s)?;
-    let end_val = parse_ipv4(end_s)?;
-    if start_val > end_val {
-      return Err(IpRangeError::InvalidRangeOrder);
-    }
-
+  fn from_bounds(input: &str, kind: RangeKind, start_val: u32, end_val: u32) -> Self {
+    // build a range from its bounds, start_val <= end_val
     let first = Ipv4Addr::from(start_val);
     let last = Ipv4Addr::from(end_val);
     let size = (end_val as u128).wrapping_sub(start_val as u128) + 1;
@@ -147,9 +262,9 @@ impl IpRange {
       None => (None, None, None, None, None),
     };
 
-    Ok(IpRange {
+    IpRange {
       input: input.to_string(),
-      kind: RangeKind::Hyphen { start: first, end: last },
+      kind,
       first,
       last,
       network,
@@ -158,23 +273,11 @@ impl IpRange {
       netmask,
       hostmask,
       size,
-    })
-  }
-
-  fn parse_wildcard(input: &str) -> Result<Self, IpRangeError> {
-    // parse wildcard notation, ex. 192.168.*
-    let parts: Vec<&str> = input.split('.').collect();
-    let specified_octets: u8 = if input == "*" {
-      0
-    } else {
-      (parts.len() as u8) - 1
-    };
-
-    let mut octs = [0u8; 4];
-    for i in 0..(specified_octets as usize) {
-      octs[i] = parts[i].parse::<u8>().map_err(|_| IpRangeError::InvalidIp(parts[i].to_string()))?;
     }
+  }
 
+  fn from_wildcard(input: &str, octs: [u8; 4], specified_octets: u8) -> Self {
+    // build a wildcard range from its fixed leading octets
     let mut first_val: u32 = 0;
     let mut last_val: u32 = 0;
 
@@ -194,7 +297,7 @@ impl IpRange {
     let mask = mask_from_prefix(prefix);
     let host = !mask;
 
-    Ok(IpRange {
+    IpRange {
       input: input.to_string(),
       kind: RangeKind::Wildcard { specified_octets },
       first: Ipv4Addr::from(first_val),
@@ -205,7 +308,43 @@ impl IpRange {
       netmask: Some(Ipv4Addr::from(mask)),
       hostmask: Some(Ipv4Addr::from(host)),
       size: size_from_prefix(prefix),
-    })
+    }
+  }
+
+  pub fn cidr(base: Ipv4Addr, prefix: u8) -> Result<Self, IpRangeError> {
+    // build a cidr block, host bits are masked as in parse, ex. 10.0.0.0/24
+    if prefix > 32 {
+      return Err(IpRangeError::InvalidPrefix(prefix));
+    }
+    let mask = mask_from_prefix(prefix);
+    let network = u32::from(base) & mask;
+    Ok(Self::canonical(network, network | !mask))
+  }
+
+  pub fn span(start: Ipv4Addr, end: Ipv4Addr) -> Result<Self, IpRangeError> {
+    // build an inclusive range between two addresses, ex. 10.0.0.1-10.0.0.9.
+    // aligned spans become a cidr block, ex. 10.0.0.0-10.0.1.255 -> 10.0.0.0/23
+    if start > end {
+      return Err(IpRangeError::InvalidRangeOrder);
+    }
+    Ok(Self::canonical(u32::from(start), u32::from(end)))
+  }
+
+  pub fn single(addr: Ipv4Addr) -> Self {
+    // build a range of one address, the /32 block 10.1.2.3/32
+    Self::canonical(u32::from(addr), u32::from(addr))
+  }
+
+  pub(crate) fn canonical(start_val: u32, end_val: u32) -> Self {
+    // build from bounds in the canonical display form, cidr when aligned and
+    // hyphen otherwise, so that kind and input name the same notation
+    let kind = match infer_cidr_from_range(start_val, end_val) {
+      Some(prefix) => RangeKind::Cidr { base: Ipv4Addr::from(start_val), prefix },
+      None => RangeKind::Hyphen { start: Ipv4Addr::from(start_val), end: Ipv4Addr::from(end_val) },
+    };
+    let mut r = Self::from_bounds("", kind, start_val, end_val);
+    r.input = r.to_string();
+    r
   }
 
   pub fn is_valid(input: &str) -> bool {
@@ -229,12 +368,64 @@ impl IpRange {
       done: false,
     }
   }
+
+  /*
+  shortest string that parses back to exactly this range, trying cidr,
+  wildcard, hyphen and single address notations
+
+  ex.
+    10.0.0.0-10.0.0.255 -> 10.0.0.0/24
+    10.0.0.0/8 -> 10.*
+    10.0.0.1/32 -> 10.0.0.1
+  */
+
+  pub fn to_best_notation(&self) -> String {
+    let mut candidates = vec![format!("{}-{}", self.first, self.last)];
+
+    if let Some(prefix) = self.prefix {
+      candidates.push(format!("{}/{}", self.first, prefix));
+      if prefix % 8 == 0 && prefix < 32 {
+        let fixed = &self.first.octets()[..(prefix / 8) as usize];
+        let mut s: String = fixed.iter().map(|o| format!("{o}.")).collect();
+        s.push('*');
+        candidates.push(s);
+      }
+    }
+    if self.size == 1 {
+      candidates.push(self.first.to_string());
+    }
+
+    candidates.into_iter().min_by_key(|s| s.len()).expect("hyphen form always exists")
+  }
 }
 
 pub fn is_valid_ip_range(input: &str) -> bool {
   IpRange::is_valid(input)
 }
 
+impl AddressClass {
+  pub fn of(ip: Ipv4Addr) -> Self {
+    // legacy class from the leading bits of the first octet
+    match ip.octets()[0] {
+      0..=127 => AddressClass::A,
+      128..=191 => AddressClass::B,
+      192..=223 => AddressClass::C,
+      224..=239 => AddressClass::D,
+      _ => AddressClass::E,
+    }
+  }
+
+  pub fn default_prefix(self) -> Option<u8> {
+    // classful network prefix, multicast and reserved space have none
+    match self {
+      AddressClass::A => Some(8),
+      AddressClass::B => Some(16),
+      AddressClass::C => Some(24),
+      AddressClass::D | AddressClass::E => None,
+    }
+  }
+}
+
 
 // ===== iterator logic =====
 
@@ -258,16 +449,29 @@ impl Iterator for IpRangeIter {
   }
 
   fn size_hint(&self) -> (usize, Option<usize>) {
-    if self.done {
-      return (0, Some(0));
+    // exact unless the count doesn't fit a usize, only on 32 bit targets
+    match usize::try_from(self.remaining()) {
+      Ok(n) => (n, Some(n)),
+      Err(_) => (usize::MAX, None),
     }
+  }
 
-    let rem = (self.end as u128)
-      .wrapping_sub(self.cur as u128)
-      .wrapping_add(1);
-    let lb = rem.min(usize::MAX as u128) as usize;
+  fn nth(&mut self, n: usize) -> Option<Self::Item> {
+    // jump straight to the nth address
+    if n as u64 >= self.remaining() {
+      self.done = true;
+      return None;
+    }
+    self.cur += n as u32;
+    self.next()
+  }
+
+  fn count(self) -> usize {
+    usize::try_from(self.remaining()).expect("address count exceeds usize")
+  }
 
-    (lb, None)
+  fn last(self) -> Option<Self::Item> {
+    (!self.done).then(|| std::net::Ipv4Addr::from(self.end))
   }
 }
 
@@ -286,10 +490,46 @@ impl DoubleEndedIterator for IpRangeIter {
 
     Some(std::net::Ipv4Addr::from(out))
   }
+
+  fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+    if n as u64 >= self.remaining() {
+      self.done = true;
+      return None;
+    }
+    self.end -= n as u32;
+    self.next_back()
+  }
 }
 
+// a /0 has 2^32 addresses, more than a 32 bit usize holds
+#[cfg(target_pointer_width = "64")]
+impl ExactSizeIterator for IpRangeIter {}
+
 impl FusedIterator for IpRangeIter {}
 
+impl IpRangeIter {
+  fn remaining(&self) -> u64 {
+    if self.done {
+      return 0;
+    }
+    (self.end - self.cur) as u64 + 1
+  }
+
+  pub fn split_at(self, index: u64) -> (IpRangeIter, IpRangeIter) {
+    // the first index addresses, and the rest
+    let empty = IpRangeIter { cur: 0, end: 0, done: true };
+    if index >= self.remaining() {
+      return (self, empty);
+    }
+    if index == 0 {
+      return (empty, self);
+    }
+
+    let mid = self.cur + index as u32;
+    (IpRangeIter { cur: self.cur, end: mid - 1, done: false }, IpRangeIter { cur: mid, end: self.end, done: false })
+  }
+}
+
 impl IntoIterator for IpRange {
   // iterate by value, ex. for ip in ip_range { ... }
   type Item = std::net::Ipv4Addr;
@@ -320,6 +560,11 @@ pub fn matches_cidr_prefix(s: &str) -> bool { full_match(Rule::cidr_prefix, s) }
 pub fn matches_range_cidr(s: &str) -> bool { full_match(Rule::range_cidr, s) }
 pub fn matches_range_hyphen(s: &str) -> bool { full_match(Rule::range_hyphen, s) }
 pub fn matches_range_wildcard(s: &str) -> bool { full_match(Rule::range_wildcard, s) }
+pub fn matches_range_count(s: &str) -> bool { full_match(Rule::range_count, s) }
+pub fn matches_range_slice(s: &str) -> bool { full_match(Rule::range_slice, s) }
+pub fn matches_range_single(s: &str) -> bool { full_match(Rule::range_single, s) }
+pub fn matches_range_pattern(s: &str) -> bool { full_match(Rule::range_pattern, s) }
+pub fn matches_range_brace(s: &str) -> bool { full_match(Rule::range_brace, s) }
 pub fn matches_range_any(s: &str) -> bool { full_match(Rule::range_any, s) }
 
 
@@ -337,23 +582,6 @@ fn full_match(rule: Rule, input: &str) -> bool {
   }
 }
 
-fn parse_ipv4(s: &str) -> Result<u32, IpRangeError> {
-  // convert quad octet string to u32
-  let parts: Vec<&str> = s.split('.').collect();
-
-  if parts.len() != 4 {
-    return Err(IpRangeError::InvalidIp(s.to_string()));
-  }
-
-  let mut acc: u32 = 0;
-  for (i, p) in parts.iter().enumerate() {
-    let oct = p.parse::<u8>().map_err(|_| IpRangeError::InvalidIp(s.to_string()))?;
-    acc |= (oct as u32) << (8 * (3 - i as u32));
-  }
-
-  Ok(acc)
-}
-
 fn mask_from_prefix(prefix: u8) -> u32 {
   // prefix in 0..=32
   if prefix == 0 { 0 }
@@ -375,7 +603,8 @@ fn infer_cidr_from_range(start: u32, end: u32) -> Option<u8> {
     return None;
   } else { size as u32 };
 
-  if start & (size_u32 - 1) != 0 {
+  // the whole address space truncates to 0, the mask wraps to u32::MAX and the prefix to /0
+  if start & size_u32.wrapping_sub(1) != 0 {
     return None;
   }
 
@@ -388,6 +617,93 @@ fn infer_cidr_from_range(start: u32, end: u32) -> Option<u8> {
 // ===== type conversions =====
 
 
+impl std::fmt::Display for AddressClass {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let c = match self {
+      AddressClass::A => "A",
+      AddressClass::B => "B",
+      AddressClass::C => "C",
+      AddressClass::D => "D",
+      AddressClass::E => "E",
+    };
+    f.write_str(c)
+  }
+}
+
+impl std::fmt::Display for IpRange {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // canonical form, cidr when the range is aligned, hyphen otherwise
+    match self.prefix {
+      Some(prefix) => write!(f, "{}/{}", self.first, prefix),
+      None => write!(f, "{}-{}", self.first, self.last),
+    }
+  }
+}
+
+impl PartialEq for IpRange {
+  fn eq(&self, other: &Self) -> bool {
+    (self.first, self.last) == (other.first, other.last)
+  }
+}
+
+impl Eq for IpRange {}
+
+impl Hash for IpRange {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    (self.first, self.last).hash(state);
+  }
+}
+
+impl PartialOrd for IpRange {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for IpRange {
+  fn cmp(&self, other: &Self) -> Ordering {
+    (self.first, self.last).cmp(&(other.first, other.last))
+  }
+}
+
+impl TryFrom<RangeInclusive<u32>> for IpRange {
+  type Error = IpRangeError;
+  fn try_from(r: RangeInclusive<u32>) -> Result<Self, Self::Error> {
+    IpRange::span(Ipv4Addr::from(*r.start()), Ipv4Addr::from(*r.end()))
+  }
+}
+
+impl From<&IpRange> for RangeInclusive<u32> {
+  fn from(r: &IpRange) -> Self {
+    u32::from(r.first)..=u32::from(r.last)
+  }
+}
+
+impl From<IpRange> for RangeInclusive<u32> {
+  fn from(r: IpRange) -> Self {
+    RangeInclusive::from(&r)
+  }
+}
+
+impl From<&IpRange> for RangeInclusive<Ipv4Addr> {
+  fn from(r: &IpRange) -> Self {
+    r.first..=r.last
+  }
+}
+
+impl From<IpRange> for RangeInclusive<Ipv4Addr> {
+  fn from(r: IpRange) -> Self {
+    r.first..=r.last
+  }
+}
+
+impl TryFrom<RangeInclusive<Ipv4Addr>> for IpRange {
+  type Error = IpRangeError;
+  fn try_from(r: RangeInclusive<Ipv4Addr>) -> Result<Self, Self::Error> {
+    IpRange::span(*r.start(), *r.end())
+  }
+}
+
 impl FromStr for IpRange {
   type Err = IpRangeError;
   fn from_str(s: &str) -> Result<Self, Self::Err> {
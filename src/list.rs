@@ -0,0 +1,205 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::set::{split_list, Operand};
+use crate::{full_match, Aliases, IpRange, IpRangeError, IpRangeSet, ParseOptions, ParseWarning, Rule};
+
+// ranges loaded from a list file, with their positions
+#[derive(Debug, Default)]
+pub struct RangeList {
+  pub entries: Vec<ListEntry>, // every valid item, in file order
+  pub errors: Vec<ListError>, // items that failed to parse, in file order
+  pub warnings: Vec<ListWarning>, // suspicious but valid items, see IpRange::warnings
+}
+
+#[derive(Debug, Clone)]
+pub struct ListEntry {
+  pub line: usize, // 1-based line number
+  pub column: usize, // 1-based column of the item
+  pub range: IpRange,
+  pub excluded: bool, // item was prefixed with "!" or "-"
+}
+
+#[derive(Debug)]
+pub struct ListError {
+  pub file: Option<String>, // path the list was loaded from, if any
+  pub line: usize,
+  pub column: usize,
+  pub error: IpRangeError,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListWarning {
+  pub file: Option<String>, // path the list was loaded from, if any
+  pub line: usize,
+  pub column: usize,
+  pub warning: ParseWarning,
+}
+
+
+// ===== core logic =====
+
+
+impl RangeList {
+
+  /*
+  parse a list of ranges the way people write them by hand
+
+  ex.
+    # office networks
+    10.1.0.0/16        ; hq
+      10.2.0.0/16, 10.3.0.0/16
+    !10.1.99.0/24      # lab
+
+  every line may hold several items as in IpRangeSet::parse,
+  ranges, braces and sparse wildcard patterns, the latter adding
+  one entry per run. aliases are only known to parse_with_aliases
+  and load_with_aliases, and add one entry per range of their set.
+  "#" and ";" start a comment, and indentation, blank lines
+  and crlf line endings are ignored. warnings are kept with their
+  position, as errors are
+  */
+
+  pub fn parse(text: &str) -> Self {
+    Self::parse_with(text, &ParseOptions::default())
+  }
+
+  pub fn parse_with(text: &str, opts: &ParseOptions) -> Self {
+    // same as parse, but every item is parsed as in IpRange::parse_with
+    let mut list = RangeList::default();
+    for (i, line) in text.lines().enumerate() {
+      list.parse_line(i + 1, line, None, opts);
+    }
+    list
+  }
+
+  pub fn parse_with_aliases(text: &str, aliases: &Aliases) -> Self {
+    // same as parse, but items may also name an alias, parsed with the options of the aliases
+    let mut list = RangeList::default();
+    for (i, line) in text.lines().enumerate() {
+      list.parse_line(i + 1, line, Some(aliases), aliases.options());
+    }
+    list
+  }
+
+  pub fn from_reader(reader: impl BufRead) -> Result<Self, IpRangeError> {
+    Self::from_reader_with(reader, &ParseOptions::default())
+  }
+
+  pub fn from_reader_with(reader: impl BufRead, opts: &ParseOptions) -> Result<Self, IpRangeError> {
+    // parse a list line by line, failing only on io errors
+    Self::read(reader, None, opts)
+  }
+
+  pub fn from_reader_with_aliases(reader: impl BufRead, aliases: &Aliases) -> Result<Self, IpRangeError> {
+    Self::read(reader, Some(aliases), aliases.options())
+  }
+
+  fn read(reader: impl BufRead, aliases: Option<&Aliases>, opts: &ParseOptions) -> Result<Self, IpRangeError> {
+    let mut list = RangeList::default();
+    for (i, line) in reader.lines().enumerate() {
+      list.parse_line(i + 1, &line?, aliases, opts);
+    }
+    Ok(list)
+  }
+
+  pub fn load(path: impl AsRef<Path>) -> Result<Self, IpRangeError> {
+    Self::load_with(path, &ParseOptions::default())
+  }
+
+  pub fn load_with(path: impl AsRef<Path>, opts: &ParseOptions) -> Result<Self, IpRangeError> {
+    // read a list file, errors are reported as file:line:column
+    let path = path.as_ref();
+    Self::from_reader_with(BufReader::new(File::open(path)?), opts).map(|list| list.located(path))
+  }
+
+  pub fn load_with_aliases(path: impl AsRef<Path>, aliases: &Aliases) -> Result<Self, IpRangeError> {
+    // same as load_with, but items may also name an alias
+    let path = path.as_ref();
+    Self::from_reader_with_aliases(BufReader::new(File::open(path)?), aliases).map(|list| list.located(path))
+  }
+
+  fn located(mut self, path: &Path) -> Self {
+    let list = &mut self;
+    for e in &mut list.errors {
+      e.file = Some(path.display().to_string());
+    }
+    for w in &mut list.warnings {
+      w.file = Some(path.display().to_string());
+    }
+    self
+  }
+
+  pub fn to_set(&self) -> IpRangeSet {
+    // merge every entry, with exclusions subtracted
+    let include: IpRangeSet = self.entries.iter().filter(|e| !e.excluded).map(|e| &e.range).collect();
+    let exclude: IpRangeSet = self.entries.iter().filter(|e| e.excluded).map(|e| &e.range).collect();
+    include.difference(&exclude)
+  }
+
+  fn parse_line(&mut self, line_no: usize, line: &str, aliases: Option<&Aliases>, opts: &ParseOptions) {
+    // a byte order mark may precede the first line
+    let line = if line_no == 1 { line.trim_start_matches('\u{feff}') } else { line };
+    let content = strip_comment(line);
+
+    for (offset, item, excluded) in split_list(content) {
+      let column = line[..offset].chars().count() + 1;
+      // items go through the same paths as in IpRangeSet::parse, only aliases
+      // and patterns stand for a set, of which every range becomes an entry
+      let parsed = if full_match(Rule::alias_name, item) {
+        match aliases {
+          Some(aliases) => aliases.resolve(item).map(|set| (set.ranges().collect(), false)),
+          None => Err(IpRangeError::UndefinedAlias(item.to_string())),
+        }
+      } else if item.contains('{') {
+        IpRange::expand_with(item, opts).map(|ranges| (ranges.collect(), true))
+      } else {
+        IpRangeSet::parse_operand(item, opts).map(|operand| match operand {
+          Operand::Range(r) => (vec![r], true),
+          Operand::Pattern(set) => (set.ranges().collect(), false),
+        })
+      };
+
+      match parsed {
+        Ok((ranges, checked)) => {
+          for range in ranges {
+            // ranges of a set are normalized, they have nothing to warn about
+            let found = if checked { range.warnings() } else { Vec::new() };
+            self.warnings.extend(found.into_iter().map(|warning| ListWarning { file: None, line: line_no, column, warning }));
+            self.entries.push(ListEntry { line: line_no, column, range, excluded });
+          }
+        }
+        Err(error) => self.errors.push(ListError { file: None, line: line_no, column, error }),
+      }
+    }
+  }
+}
+
+pub(crate) fn strip_comment(line: &str) -> &str {
+  // everything from the first "#" or ";" on is a comment
+  line.split(['#', ';']).next().unwrap_or_default()
+}
+
+
+// ===== type conversions =====
+
+
+impl fmt::Display for ListError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some(file) = &self.file {
+      write!(f, "{file}:")?;
+    }
+    write!(f, "{}:{}: {}", self.line, self.column, self.error)
+  }
+}
+
+impl fmt::Display for ListWarning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some(file) = &self.file {
+      write!(f, "{file}:")?;
+    }
+    write!(f, "{}:{}: {}", self.line, self.column, self.warning)
+  }
+}
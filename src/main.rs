@@ -1,19 +1,70 @@
-use netdec::{IpRange, RangeKind};
+use netdec::{
+  AddressMode, Aliases, Evaluated, HostBits, IpPattern, IpRange, IpRangeError, IpRangeSet, Notation, ParseOptions, RangeKind,
+  RangeList, Shard, ShardStrategy, WildcardRange,
+};
 use std::env;
+use std::fmt;
+use std::mem;
 use std::process;
 
 fn print_usage() {
   eprintln!("
 usage:
-  {bin} <RANGE>
+  {bin} [OPTIONS] <RANGE>
+  {bin} <RANGE>... [!<RANGE>...]
+  {bin} --expr <EXPR>
+  {bin} --file <FILE>...
+  {bin} sample [OPTIONS] [-n N] [--per-block PREFIX] [--seed N] <RANGE>...
+
+options:
+  --classful          infer the classful prefix of a bare address
+  --reject-host-bits  reject cidr ranges with host bits set, ex. 10.0.0.5/24
+  --zero-padded       accept zero padded numbers, ex. 010.000.000.001
+  --address-mode MODE read legacy inet_aton addresses, ex. 10.1 or 0x0a000001
+                      (lenient accepts them, strict reports them as ambiguous)
+  --trim              ignore whitespace around a range
+  --deny-warnings     fail on suspicious input, ex. 0.0.0.0/0
+  --disable LIST      disable notations, ex. count,slice
+                      (cidr, hyphen, wildcard, single, count, slice)
+  --shard I/N         print the addresses of shard I of N, ex. 0/4
+  --shard-order ORDER contiguous (default), interleaved or shuffled
+  --seed N            seed of the shuffled order, default 0
+  -e, --expr EXPR     evaluate a set expression
+  -a, --aliases F     load named ranges from a definitions file
+  -f, --file F        load ranges from a list file, may be repeated
+
+sample options, besides the parse options above:
+  -n N                number of addresses, default 1
+  --per-block PREFIX  n addresses from every aligned block, ex. 24
+  --seed N            seed of the sample, default 0
 
 examples:
   {bin} 192.168.0.0/24
   {bin} 192.168.0.1-192.168.0.10
   {bin} 10.*
+  {bin} 10.*.0.1
+  {bin} 10.1.2.3
+  {bin} --classful 172.16.0.0
+  {bin} --reject-host-bits 10.0.0.5/24
+  {bin} --address-mode lenient 0x0a000001-10.1.0.9
+  {bin} 10.0.0.0+256
+  {bin} '10.0.0.0/24[10:20]'
+  {bin} '10.{{0,2}}.{{1..3}}.0/24'
+  {bin} 10.0.0.0/24 10.0.1.5-10.0.1.9 '!10.0.0.1'
+  {bin} --expr '(10.0.0.0/8 - 10.1.0.0/16) | 192.168.*'
+  {bin} --aliases networks.txt --expr 'trusted - vpn'
+  {bin} --file blocklist.txt
+  {bin} --shard 2/8 --shard-order shuffled --seed 7 10.0.0.0/16
+  {bin} sample -n 5 --seed 42 10.0.0.0/8
+  {bin} sample --per-block 24 10.0.0.0/16
+
+expressions:
+  a | b  union            a & b  intersection
+  a - b  difference       a ^ b  symmetric difference
+  ~a     complement       ( )    grouping
 
 notes:
-  in shells like bash/zsh/fish, '*' is a glob. quote or escape it
+  in shells like bash/zsh/fish, '*' is a glob and braces expand. quote or escape them
 ",
     bin = env!("CARGO_PKG_NAME")
   );
@@ -32,27 +83,324 @@ fn main() {
     process::exit(0);
   }
 
-  if argv.len() > 1 {
-    eprintln!("
-error: received {} arguments
-hint: quote the wildcard, e.g.:
-  {bin} \"*\"
-",
-      argv.len(),
-      bin = env!("CARGO_PKG_NAME")
-    );
+  if argv[0] == "sample" {
+    sample(&argv[1..]);
+  }
+
+  // flags come first, the remaining arguments are ranges
+  let mut opts = ParseOptions::new();
+  let mut deny_warnings = false;
+  let mut shard: Option<(u64, u64)> = None;
+  let mut shard_order = "contiguous";
+  let mut seed: u64 = 0;
+  let mut expr: Option<&String> = None;
+  let mut aliases_path: Option<&String> = None;
+  let mut files: Vec<&String> = Vec::new();
+  let mut args: Vec<&String> = Vec::new();
+  let mut it = argv.iter();
+  while let Some(a) = it.next() {
+    if parse_flag(a, &mut it, &mut opts, &mut deny_warnings) {
+      continue;
+    }
+    match a.as_str() {
+      "--shard" => {
+        let v = it.next().unwrap_or_else(|| missing_value(a));
+        shard = Some(parse_shard(v).unwrap_or_else(|| invalid_value(a, v)));
+      }
+      "--shard-order" => shard_order = it.next().unwrap_or_else(|| missing_value(a)),
+      "--seed" => {
+        let v = it.next().unwrap_or_else(|| missing_value(a));
+        seed = v.parse().unwrap_or_else(|_| invalid_value(a, v));
+      }
+      "-e" | "--expr" => expr = Some(it.next().unwrap_or_else(|| missing_value(a))),
+      "-a" | "--aliases" => aliases_path = Some(it.next().unwrap_or_else(|| missing_value(a))),
+      "-f" | "--file" => files.push(it.next().unwrap_or_else(|| missing_value(a))),
+      _ => args.push(a),
+    }
+  }
+
+  let strategy = match shard_order {
+    "contiguous" => ShardStrategy::Contiguous,
+    "interleaved" => ShardStrategy::Interleaved,
+    "shuffled" => ShardStrategy::Shuffled(seed),
+    other => invalid_value("--shard-order", other),
+  };
+
+  let aliases = match aliases_path.map(Aliases::load) {
+    None => None,
+    Some(Ok(aliases)) => Some(aliases.with_options(opts.clone())),
+    Some(Err(e)) => {
+      eprintln!("error: {}: {e}", aliases_path.unwrap());
+      process::exit(2);
+    }
+  };
+
+  if let Some(expr) = expr {
+    // anything else would be silently left out of the result
+    if !args.is_empty() || !files.is_empty() {
+      eprintln!("error: --expr takes no other ranges or files, add them to the expression, ex. 'a | b'");
+      process::exit(2);
+    }
+    let evaluated = match &aliases {
+      Some(aliases) => IpRangeSet::eval_with_aliases_and_warnings(expr, aliases),
+      None => IpRangeSet::eval_with_warnings(expr, &opts),
+    };
+    match evaluated {
+      Ok(Evaluated { set, warnings }) => {
+        warn(&warnings, deny_warnings);
+        match shard {
+          Some((i, n)) => print_shard(set.shard_with(i, n, strategy)),
+          None => print_set(&set),
+        }
+        process::exit(0);
+      }
+
+      Err(e) => {
+        report(&e);
+        process::exit(2);
+      }
+    }
+  }
+
+  if args.is_empty() && files.is_empty() {
+    print_usage();
     process::exit(2);
   }
 
-  let arg = &argv[0];
-  match arg.parse::<IpRange>() {
-    Ok(r) => {
-      print_range(&r);
+  // several ranges are merged into a single set, as are aliases and list files
+  if args.len() > 1 || args.first().is_some_and(|a| is_list(a)) || aliases.is_some() || !files.is_empty() {
+    let mut set = IpRangeSet::new();
+    let mut failed = false;
+    let mut warnings: Vec<String> = Vec::new();
+
+    for path in &files {
+      let loaded = match &aliases {
+        Some(aliases) => RangeList::load_with_aliases(path, aliases),
+        None => RangeList::load_with(path, &opts),
+      };
+      match loaded {
+        Ok(list) => {
+          set = set.union(&list.to_set());
+          for e in &list.errors {
+            eprintln!("error: {e}");
+          }
+          failed |= !list.errors.is_empty();
+          warnings.extend(list.warnings.iter().map(|w| w.to_string()));
+        }
+
+        Err(e) => {
+          eprintln!("error: {path}: {e}");
+          process::exit(2);
+        }
+      }
+    }
+
+    let joined = args.iter().map(|a| a.as_str()).collect::<Vec<_>>().join(" ");
+    let parsed = match &aliases {
+      Some(aliases) => IpRangeSet::parse_with_aliases(&joined, aliases),
+      None => IpRangeSet::parse_with(&joined, &opts),
+    };
+    for e in &parsed.errors {
+      eprintln!("error: {e}");
+    }
+    if !parsed.errors.is_empty() {
+      eprintln!("hint: in shells like bash/zsh/fish, quote wildcards, e.g. \"*\"\n");
+    }
+
+    // a partial set is easy to mistake for the whole one, so print nothing
+    if failed || !parsed.errors.is_empty() {
+      process::exit(2);
+    }
+    warnings.extend(parsed.warnings.iter().map(|w| w.to_string()));
+    warn(&warnings, deny_warnings);
+
+    let set = set.union(&parsed.set);
+    match shard {
+      Some((i, n)) => print_shard(set.shard_with(i, n, strategy)),
+      None => print_set(&set),
+    }
+    process::exit(0);
+  }
+
+  let arg = args[0];
+  if arg.contains('{') {
+    match IpRange::expand_with(arg, &opts) {
+      Ok(ranges) => {
+        // every expansion is checked before the first one is printed
+        let ranges: Vec<IpRange> = ranges.collect();
+        let warnings: Vec<String> = ranges.iter().flat_map(|r| r.warnings().into_iter().map(|w| format!("{}: {w}", r.input))).collect();
+        warn(&warnings, deny_warnings);
+        ranges.iter().for_each(print_range);
+        process::exit(0);
+      }
+
+      Err(e) => {
+        report(&e);
+        process::exit(2);
+      }
+    }
+  }
+
+  match IpRange::parse_with_warnings(arg, &opts) {
+    Ok(parsed) => {
+      warn(&parsed.warnings, deny_warnings);
+      match shard {
+        Some((i, n)) => print_shard(parsed.range.shard_with(i, n, strategy)),
+        None => print_range(&parsed.range),
+      }
       process::exit(0);
     }
-    
+
+    // wildcards before a fixed octet aren't a single range, describe the pattern instead
+    Err(IpRangeError::NonContiguous(_)) => {
+      if let Ok(WildcardRange::Sparse(p)) = WildcardRange::parse_with(arg, &opts) {
+        print_pattern(&p);
+        process::exit(0);
+      }
+      eprintln!("error: range is not contiguous: {arg}");
+      process::exit(2);
+    }
+
     Err(e) => {
-      eprintln!("error: {e}");
+      report(&e);
+      process::exit(2);
+    }
+  }
+}
+
+fn sample(argv: &[String]) -> ! {
+  // netdec sample, prints n random addresses of the ranges, one per line
+  let mut n: u64 = 1;
+  let mut per_block: Option<u8> = None;
+  let mut seed: u64 = 0;
+  let mut opts = ParseOptions::new();
+  let mut deny_warnings = false;
+  let mut args: Vec<&str> = Vec::new();
+  let mut it = argv.iter();
+  while let Some(a) = it.next() {
+    if parse_flag(a, &mut it, &mut opts, &mut deny_warnings) {
+      continue;
+    }
+    match a.as_str() {
+      "-n" => {
+        let v = it.next().unwrap_or_else(|| missing_value(a));
+        n = v.parse().unwrap_or_else(|_| invalid_value(a, v));
+      }
+      "--per-block" => {
+        let v = it.next().unwrap_or_else(|| missing_value(a));
+        per_block = Some(v.parse().ok().filter(|p| *p <= 32).unwrap_or_else(|| invalid_value(a, v)));
+      }
+      "--seed" => {
+        let v = it.next().unwrap_or_else(|| missing_value(a));
+        seed = v.parse().unwrap_or_else(|_| invalid_value(a, v));
+      }
+      _ => args.push(a),
+    }
+  }
+
+  if args.is_empty() {
+    print_usage();
+    process::exit(2);
+  }
+
+  let parsed = IpRangeSet::parse_with(&args.join(" "), &opts);
+  for e in &parsed.errors {
+    eprintln!("error: {e}");
+  }
+  if !parsed.errors.is_empty() {
+    process::exit(2);
+  }
+  warn(&parsed.warnings, deny_warnings);
+
+  let addrs = match per_block {
+    Some(prefix) => parsed.set.sample_per_block(prefix, n, seed).expect("--per-block is at most 32"),
+    None => parsed.set.sample(n, seed),
+  };
+  for ip in addrs {
+    println!("{ip}");
+  }
+  process::exit(0);
+}
+
+fn parse_flag<'a>(
+  flag: &str,
+  it: &mut impl Iterator<Item = &'a String>,
+  opts: &mut ParseOptions,
+  deny_warnings: &mut bool,
+) -> bool {
+  // the parse flags shared by every command, false if flag is not one of them
+  match flag {
+    "--classful" => *opts = mem::take(opts).classful(true),
+    "--reject-host-bits" => *opts = mem::take(opts).host_bits(HostBits::Reject),
+    "--zero-padded" => *opts = mem::take(opts).zero_padded(true),
+    "--trim" => *opts = mem::take(opts).trim(true),
+    "--address-mode" => {
+      let v = it.next().unwrap_or_else(|| missing_value(flag));
+      *opts = match v.as_str() {
+        "lenient" => mem::take(opts).address_mode(AddressMode::Lenient),
+        "strict" => mem::take(opts).address_mode(AddressMode::Strict),
+        _ => invalid_value(flag, v),
+      };
+    }
+    "--deny-warnings" => *deny_warnings = true,
+    "--disable" => {
+      let list = it.next().unwrap_or_else(|| missing_value(flag));
+      for name in list.split(',') {
+        match name.trim().parse::<Notation>() {
+          Ok(n) => *opts = mem::take(opts).notation(n, false),
+          Err(e) => {
+            eprintln!("error: {e}");
+            process::exit(2);
+          }
+        }
+      }
+    }
+    _ => return false,
+  }
+  true
+}
+
+fn warn(warnings: &[impl fmt::Display], deny: bool) {
+  // warnings go to stderr, --deny-warnings turns any of them into a failure
+  for w in warnings {
+    eprintln!("warning: {w}");
+  }
+  if deny && !warnings.is_empty() {
+    eprintln!("error: warnings denied by --deny-warnings");
+    process::exit(2);
+  }
+}
+
+fn report(e: &IpRangeError) {
+  // syntax errors point at the offending part of the input
+  match e {
+    IpRangeError::Syntax(d) => eprint!("\n{}\n", d.render()),
+    e => eprintln!("error: {e}"),
+  }
+}
+
+fn missing_value(flag: &str) -> ! {
+  eprintln!("error: {flag} expects a value");
+  process::exit(2);
+}
+
+fn invalid_value(flag: &str, value: &str) -> ! {
+  eprintln!("error: invalid value for {flag}: {value}");
+  process::exit(2);
+}
+
+fn parse_shard(s: &str) -> Option<(u64, u64)> {
+  // "i/n", shards are numbered from 0
+  let (i, n) = s.split_once('/')?;
+  Some((i.parse().ok()?, n.parse().ok()?))
+}
+
+fn print_shard(shard: Result<Shard, IpRangeError>) {
+  // one address per line, for piping into other tools
+  match shard {
+    Ok(shard) => shard.for_each(|ip| println!("{ip}")),
+    Err(e) => {
+      report(&e);
       process::exit(2);
     }
   }
@@ -60,6 +408,8 @@ hint: quote the wildcard, e.g.:
 
 fn print_range(r: &IpRange) {
   println!("\ninput: {}", r.input);
+  println!("canonical: {r}");
+  println!("shortest: {}", r.to_best_notation());
   println!("kind: {}", kind_label(&r.kind));
   println!("first: {}", r.first);
   println!("last: {}", r.last);
@@ -84,6 +434,45 @@ fn print_range(r: &IpRange) {
   println!()
 }
 
+fn print_set(set: &IpRangeSet) {
+  for r in set.ranges() {
+    print_range(&r);
+  }
+
+  let count = set.ranges().count();
+  println!("total: {} address{} in {} range{}\n",
+    set.size(),
+    if set.size() == 1 { "" } else { "es" },
+    count,
+    if count == 1 { "" } else { "s" });
+}
+
+fn is_list(arg: &str) -> bool {
+  // commas outside braces, whitespace or a leading exclusion mark a list
+  let mut depth = 0;
+  for c in arg.chars() {
+    match c {
+      '{' => depth += 1,
+      '}' => depth -= 1,
+      ',' if depth == 0 => return true,
+      c if c.is_whitespace() => return true,
+      _ => {}
+    }
+  }
+  arg.starts_with('!')
+}
+
+fn print_pattern(p: &IpPattern) {
+  let wildcards = p.octets.iter().filter(|o| o.is_none()).count();
+
+  println!("\ninput: {}", p.input);
+  println!("kind: pattern ({} wildcard octet{})", wildcards, if wildcards == 1 { "" } else { "s" });
+  println!("first: {}", p.iter().next().unwrap());
+  println!("last: {}", p.iter().next_back().unwrap());
+  println!("size: {}", p.size());
+  println!()
+}
+
 fn kind_label(k: &RangeKind) -> String {
   match k {
     RangeKind::Cidr { base, prefix } => format!("cidr (base={base}, /{prefix})"),
@@ -93,5 +482,12 @@ fn kind_label(k: &RangeKind) -> String {
         specified_octets,
         if *specified_octets == 1 { "" } else { "s" })
     }
+    RangeKind::Count { start, count } => format!("count (start={start}, +{count})"),
+    RangeKind::Slice { from, to, start, end, .. } => {
+      let idx = |i: &Option<i64>| i.map(|i| i.to_string()).unwrap_or_default();
+      format!("slice ([{}:{}] of {from}-{to})", idx(start), idx(end))
+    }
+    RangeKind::Single { .. } => "single".to_string(),
+    RangeKind::Classful { base, class } => format!("classful (base={base}, class {class})"),
   }
 }
@@ -0,0 +1,184 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::AddressMode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostBits {
+  Mask, // clear host bits silently, 10.0.0.5/24 -> 10.0.0.0/24, see IpRange::parse_with_warnings
+  Reject // fail with IpRangeError::HostBitsSet
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+  Cidr, // a.b.c.d/nn
+  Hyphen, // a.b.c.d-e.f.g.h
+  Wildcard, // a.b.*, a.b.*.*
+  Single, // a.b.c.d
+  Count, // a.b.c.d+n
+  Slice // any of the above followed by [start:end]
+}
+
+// parsing policy for IpRange::parse_with, the default matches IpRange::parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+  pub(crate) host_bits: HostBits,
+  pub(crate) zero_padded: bool,
+  pub(crate) trim: bool,
+  pub(crate) classful: bool,
+  pub(crate) address_mode: Option<AddressMode>,
+  pub(crate) disabled: Vec<Notation>,
+}
+
+
+// ===== builder =====
+
+
+impl Default for ParseOptions {
+  fn default() -> Self {
+    ParseOptions {
+      host_bits: HostBits::Mask,
+      zero_padded: false,
+      trim: false,
+      classful: false,
+      address_mode: None,
+      disabled: Vec::new(),
+    }
+  }
+}
+
+impl ParseOptions {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn host_bits(mut self, host_bits: HostBits) -> Self {
+    // what to do with host bits in cidr notation, ex. 10.0.0.5/24.
+    // parse_with masks without a word, parse_with_warnings also reports
+    // ParseWarning::HostBitsSet for every masked range
+    self.host_bits = host_bits;
+    self
+  }
+
+  pub fn zero_padded(mut self, allow: bool) -> Self {
+    // accept zero padded numbers as decimal, ex. 010.000.000.001/024
+    self.zero_padded = allow;
+    self
+  }
+
+  pub fn trim(mut self, trim: bool) -> Self {
+    // ignore surrounding whitespace
+    self.trim = trim;
+    self
+  }
+
+  pub fn classful(mut self, classful: bool) -> Self {
+    // infer the classful prefix of a bare address, ex. 10.0.0.0 -> 10.0.0.0/8
+    self.classful = classful;
+    self
+  }
+
+  pub fn address_mode(mut self, mode: AddressMode) -> Self {
+    // read addresses as parse_ipv4_with does, ex. 10.1/16 or 0x0a000001.
+    // strict reports legacy forms as IpRangeError::AmbiguousAddress, by default
+    // they are syntax errors. zero_padded strips zeros first, so 010 stays decimal
+    self.address_mode = Some(mode);
+    self
+  }
+
+  pub fn notation(mut self, notation: Notation, enabled: bool) -> Self {
+    // enable or disable a single notation, all are enabled by default
+    self.disabled.retain(|&n| n != notation);
+    if !enabled {
+      self.disabled.push(notation);
+    }
+    self
+  }
+
+  pub fn allows(&self, notation: Notation) -> bool {
+    !self.disabled.contains(&notation)
+  }
+
+  pub(crate) fn normalize<'a>(&self, input: &'a str) -> Cow<'a, str> {
+    // the text the scanner sees, trimmed and without zero padding as configured
+    let text = if self.trim { input.trim() } else { input };
+    if self.zero_padded {
+      Cow::Owned(strip_zero_padding(text))
+    } else {
+      Cow::Borrowed(text)
+    }
+  }
+}
+
+pub(crate) fn strip_zero_padding(s: &str) -> String {
+  // drop leading zeros from every number, keeping at least one digit.
+  // digits after a letter belong to a name, ex. office01
+  let mut out = String::with_capacity(s.len());
+  let mut in_number = false;
+  let mut in_word = false;
+  let mut pending_zero = false;
+
+  for c in s.chars() {
+    if c.is_ascii_alphabetic() || c == '_' {
+      in_word = true;
+    } else if !c.is_ascii_digit() {
+      in_word = false;
+    }
+
+    if c.is_ascii_digit() && !in_word {
+      if !in_number && c == '0' {
+        pending_zero = true;
+        continue;
+      }
+      in_number = true;
+      pending_zero = false;
+      out.push(c);
+    } else {
+      if pending_zero {
+        out.push('0');
+      }
+      in_number = false;
+      pending_zero = false;
+      out.push(c);
+    }
+  }
+  if pending_zero {
+    out.push('0');
+  }
+
+  out
+}
+
+
+// ===== type conversions =====
+
+
+impl fmt::Display for Notation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Notation::Cidr => "cidr",
+      Notation::Hyphen => "hyphen",
+      Notation::Wildcard => "wildcard",
+      Notation::Single => "single",
+      Notation::Count => "count",
+      Notation::Slice => "slice",
+    };
+    f.write_str(name)
+  }
+}
+
+impl std::str::FromStr for Notation {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "cidr" => Ok(Notation::Cidr),
+      "hyphen" => Ok(Notation::Hyphen),
+      "wildcard" => Ok(Notation::Wildcard),
+      "single" => Ok(Notation::Single),
+      "count" => Ok(Notation::Count),
+      "slice" => Ok(Notation::Slice),
+      _ => Err(format!("unknown notation: {s}")),
+    }
+  }
+}
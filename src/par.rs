@@ -0,0 +1,109 @@
+use std::net::Ipv4Addr;
+
+use rayon::iter::plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer};
+use rayon::prelude::*;
+
+use crate::{IpRange, IpRangeIter, IpRangeSet};
+
+/*
+parallel iterators over addresses, with the rayon feature
+
+work is split by halving address intervals, each thread then walks its
+own sub interval sequentially
+
+ex.
+  use rayon::prelude::*;
+  let hits = r.par_iter().filter(|ip| lookup(*ip)).count();
+*/
+
+pub struct ParIpRangeIter {
+  iter: IpRangeIter,
+}
+
+pub struct ParIpRangeSetIter {
+  intervals: Vec<(u32, u32)>,
+}
+
+// splits an address interval in halves until rayon stops asking
+struct IntervalProducer(IpRangeIter);
+
+
+// ===== core logic =====
+
+
+fn interval(&(start, end): &(u32, u32)) -> ParIpRangeIter {
+  ParIpRangeIter { iter: IpRangeIter { cur: start, end, done: false } }
+}
+
+impl UnindexedProducer for IntervalProducer {
+  type Item = Ipv4Addr;
+
+  fn split(self) -> (Self, Option<Self>) {
+    let len = self.0.remaining();
+    if len < 2 {
+      return (self, None);
+    }
+    let (head, tail) = self.0.split_at(len / 2);
+    (IntervalProducer(head), Some(IntervalProducer(tail)))
+  }
+
+  fn fold_with<F: Folder<Self::Item>>(self, folder: F) -> F {
+    folder.consume_iter(self.0)
+  }
+}
+
+
+// ===== iterators =====
+
+
+impl ParallelIterator for ParIpRangeIter {
+  type Item = Ipv4Addr;
+
+  fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+    bridge_unindexed(IntervalProducer(self.iter), consumer)
+  }
+}
+
+impl ParallelIterator for ParIpRangeSetIter {
+  type Item = Ipv4Addr;
+
+  fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+    self.intervals.par_iter().flat_map(interval).drive_unindexed(consumer)
+  }
+}
+
+impl IntoParallelIterator for IpRange {
+  type Iter = ParIpRangeIter;
+  type Item = Ipv4Addr;
+
+  fn into_par_iter(self) -> Self::Iter {
+    (&self).into_par_iter()
+  }
+}
+
+impl IntoParallelIterator for &IpRange {
+  type Iter = ParIpRangeIter;
+  type Item = Ipv4Addr;
+
+  fn into_par_iter(self) -> Self::Iter {
+    ParIpRangeIter { iter: self.iter() }
+  }
+}
+
+impl IntoParallelIterator for IpRangeSet {
+  type Iter = ParIpRangeSetIter;
+  type Item = Ipv4Addr;
+
+  fn into_par_iter(self) -> Self::Iter {
+    (&self).into_par_iter()
+  }
+}
+
+impl IntoParallelIterator for &IpRangeSet {
+  type Iter = ParIpRangeSetIter;
+  type Item = Ipv4Addr;
+
+  fn into_par_iter(self) -> Self::Iter {
+    ParIpRangeSetIter { intervals: self.intervals().to_vec() }
+  }
+}
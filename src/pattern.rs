@@ -0,0 +1,236 @@
+use std::iter::FusedIterator;
+use std::net::Ipv4Addr;
+
+use crate::diagnostic::diagnose;
+use crate::{full_match, IpRange, IpRangeError, Notation, ParseOptions, RangeKind, Rule};
+
+// octet pattern where any octet may be a wildcard, ex. 10.*.0.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpPattern {
+  pub input: String, // original input
+  pub octets: [Option<u8>; 4], // fixed octets, None for a wildcard
+}
+
+// result of parsing a wildcard pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WildcardRange {
+  Contiguous(IpRange), // every wildcard is trailing, ex. 10.*.*.*
+  Sparse(IpPattern) // a wildcard precedes a fixed octet, ex. 10.*.0.1
+}
+
+#[derive(Clone, Debug)]
+pub struct IpPatternIter {
+  octets: [Option<u8>; 4],
+  cur: u64,
+  end: u64,
+  done: bool,
+}
+
+
+// ===== core logic =====
+
+
+impl IpPattern {
+
+  /*
+  parse a wildcard pattern
+
+  supports:
+  - any octet as wildcard: "10.*.0.1", "*.*.*.1"
+  - trailing shorthand: "*", "a.*", "a.b.*", "a.b.c.*"
+  */
+
+  pub fn parse(input: &str) -> Result<Self, IpRangeError> {
+    if !full_match(Rule::range_pattern, input) && !full_match(Rule::range_wildcard, input) {
+      return Err(IpRangeError::Syntax(Box::new(diagnose(input, Rule::range_pattern))));
+    }
+
+    // the trailing shorthand leaves out wildcard octets
+    let mut octets = [None; 4];
+    for (i, part) in input.split('.').enumerate() {
+      if part != "*" {
+        octets[i] = Some(part.parse::<u8>().map_err(|_| IpRangeError::InvalidIp(part.to_string()))?);
+      }
+    }
+
+    Ok(IpPattern { input: input.to_string(), octets })
+  }
+
+  pub fn parse_with(input: &str, opts: &ParseOptions) -> Result<Self, IpRangeError> {
+    // same as parse, trimmed and without zero padding as configured, input keeps the text as given
+    if !opts.allows(Notation::Wildcard) {
+      return Err(IpRangeError::NotationDisabled(Notation::Wildcard));
+    }
+    let mut pattern = Self::parse(&opts.normalize(input))?;
+    pattern.input = input.to_string();
+    Ok(pattern)
+  }
+
+  pub fn is_contiguous(&self) -> bool {
+    // contiguous if no fixed octet follows a wildcard
+    let fixed = self.octets.iter().take_while(|o| o.is_some()).count();
+    self.octets[fixed..].iter().all(|o| o.is_none())
+  }
+
+  pub fn contains(&self, ip: Ipv4Addr) -> bool {
+    // boolean check for whether every fixed octet matches
+    self.octets.iter().zip(ip.octets()).all(|(o, x)| o.is_none_or(|o| o == x))
+  }
+
+  pub fn size(&self) -> u128 {
+    // 256 addresses per wildcard octet
+    1u128 << (8 * self.octets.iter().filter(|o| o.is_none()).count())
+  }
+
+  pub fn iter(&self) -> IpPatternIter {
+    // iterator over all ips matching the pattern, in ascending order
+    IpPatternIter {
+      octets: self.octets,
+      cur: 0,
+      end: (self.size() - 1) as u64,
+      done: false,
+    }
+  }
+}
+
+impl WildcardRange {
+  pub fn parse(input: &str) -> Result<Self, IpRangeError> {
+    // parse a wildcard pattern, collapsing it to a plain range when contiguous
+    Self::parse_with(input, &ParseOptions::default())
+  }
+
+  pub fn parse_with(input: &str, opts: &ParseOptions) -> Result<Self, IpRangeError> {
+    // same as parse, under the policy of IpPattern::parse_with
+    let pattern = IpPattern::parse_with(input, opts)?;
+    if !pattern.is_contiguous() {
+      return Ok(WildcardRange::Sparse(pattern));
+    }
+
+    let specified_octets = pattern.octets.iter().filter(|o| o.is_some()).count() as u8;
+    let octs = pattern.octets.map(|o| o.unwrap_or(0));
+    Ok(WildcardRange::Contiguous(IpRange::from_wildcard(input, octs, specified_octets)))
+  }
+
+  pub fn contains(&self, ip: Ipv4Addr) -> bool {
+    match self {
+      WildcardRange::Contiguous(r) => r.contains(ip),
+      WildcardRange::Sparse(p) => p.contains(ip),
+    }
+  }
+
+  pub fn size(&self) -> u128 {
+    match self {
+      WildcardRange::Contiguous(r) => r.size,
+      WildcardRange::Sparse(p) => p.size(),
+    }
+  }
+
+  pub fn iter(&self) -> IpPatternIter {
+    match self {
+      WildcardRange::Contiguous(r) => {
+        // rebuild the octet pattern from the fixed leading octets
+        let specified = match r.kind {
+          RangeKind::Wildcard { specified_octets } => specified_octets as usize,
+          _ => unreachable!("contiguous wildcard ranges have a wildcard kind"),
+        };
+        let mut octets = r.first.octets().map(Some);
+        octets[specified..].fill(None);
+
+        IpPatternIter { octets, cur: 0, end: (r.size - 1) as u64, done: false }
+      }
+      WildcardRange::Sparse(p) => p.iter(),
+    }
+  }
+}
+
+
+// ===== iterator logic =====
+
+
+impl IpPatternIter {
+  fn addr_at(&self, index: u64) -> Ipv4Addr {
+    // spread the index over the wildcard octets, the leftmost being most significant
+    let mut rem = index;
+    let mut octs = [0u8; 4];
+    for i in (0..4).rev() {
+      octs[i] = match self.octets[i] {
+        Some(o) => o,
+        None => {
+          let o = (rem & 0xff) as u8;
+          rem >>= 8;
+          o
+        }
+      };
+    }
+    Ipv4Addr::from(octs)
+  }
+}
+
+impl Iterator for IpPatternIter {
+  type Item = Ipv4Addr;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None;
+    }
+
+    let out = self.cur;
+    if self.cur == self.end {
+      self.done = true;
+    } else {
+      self.cur += 1;
+    }
+
+    Some(self.addr_at(out))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    // exact unless the count doesn't fit a usize, only on 32 bit targets
+    let rem = if self.done { 0 } else { self.end - self.cur + 1 };
+    match usize::try_from(rem) {
+      Ok(n) => (n, Some(n)),
+      Err(_) => (usize::MAX, None),
+    }
+  }
+}
+
+// * matches 2^32 addresses, more than a 32 bit usize holds
+#[cfg(target_pointer_width = "64")]
+impl ExactSizeIterator for IpPatternIter {}
+
+impl DoubleEndedIterator for IpPatternIter {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None;
+    }
+
+    let out = self.end;
+    if self.cur == self.end {
+      self.done = true;
+    } else {
+      self.end -= 1;
+    }
+
+    Some(self.addr_at(out))
+  }
+}
+
+impl FusedIterator for IpPatternIter {}
+
+impl IntoIterator for &IpPattern {
+  type Item = Ipv4Addr;
+  type IntoIter = IpPatternIter;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl IntoIterator for &WildcardRange {
+  type Item = Ipv4Addr;
+  type IntoIter = IpPatternIter;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+
+use crate::shuffle::splitmix64;
+use crate::{mask_from_prefix, IpRange, IpRangeError, IpRangeSet};
+
+// splitmix64 stream, enough for sampling and reproducible across platforms
+struct Rng(u64);
+
+
+// ===== core logic =====
+
+
+impl IpRange {
+  /*
+  n distinct addresses picked uniformly at random, in ascending order.
+  deterministic for a given seed, and the whole range when n >= size
+
+  uses floyd's algorithm, so memory and time grow with n, not the range.
+  past half the range the addresses left out are picked instead
+  */
+
+  pub fn sample(&self, n: u64, seed: u64) -> Vec<Ipv4Addr> {
+    sample_intervals(&[(u32::from(self.first), u32::from(self.last))], n, seed)
+  }
+
+  pub fn sample_per_block(&self, prefix: u8, k: u64, seed: u64) -> Result<Vec<Ipv4Addr>, IpRangeError> {
+    // k addresses from every aligned block of the prefix, ex. one per /24.
+    // fails on a prefix past 32
+    sample_blocks(&[(u32::from(self.first), u32::from(self.last))], prefix, k, seed)
+  }
+}
+
+impl IpRangeSet {
+  pub fn sample(&self, n: u64, seed: u64) -> Vec<Ipv4Addr> {
+    // same as IpRange::sample, over every address of the set
+    sample_intervals(self.intervals(), n, seed)
+  }
+
+  pub fn sample_per_block(&self, prefix: u8, k: u64, seed: u64) -> Result<Vec<Ipv4Addr>, IpRangeError> {
+    // same as IpRange::sample_per_block, ranges sharing a block are sampled together
+    sample_blocks(self.intervals(), prefix, k, seed)
+  }
+}
+
+impl Rng {
+  fn below(&mut self, bound: u64) -> u64 {
+    // uniform in 0..bound, rejecting the biased tail
+    let zone = u64::MAX - u64::MAX % bound;
+    loop {
+      self.0 = self.0.wrapping_add(1);
+      let x = splitmix64(self.0);
+      if x < zone {
+        return x % bound;
+      }
+    }
+  }
+}
+
+fn sample_intervals(intervals: &[(u32, u32)], n: u64, seed: u64) -> Vec<Ipv4Addr> {
+  let size: u64 = intervals.iter().map(|&(a, b)| (b - a) as u64 + 1).sum();
+  let n = n.min(size);
+  let mut rng = Rng(splitmix64(seed));
+
+  // past half the addresses, pick the ones to leave out instead
+  if n > size / 2 {
+    let mut skipped = floyd(size, size - n, &mut rng).into_iter().peekable();
+    let kept = (0..size).filter(move |&i| skipped.next_if_eq(&i).is_none());
+    addresses(intervals, kept, n)
+  } else {
+    addresses(intervals, floyd(size, n, &mut rng).into_iter(), n)
+  }
+}
+
+fn floyd(size: u64, k: u64, rng: &mut Rng) -> Vec<u64> {
+  // floyd's algorithm, a uniform k element subset of 0..size, sorted.
+  // the capacity is only a hint, large k grow the set as needed
+  let mut picked = HashSet::with_capacity(k.min(1 << 20) as usize);
+  for j in size - k..size {
+    let t = rng.below(j + 1);
+    if !picked.insert(t) {
+      picked.insert(j);
+    }
+  }
+
+  let mut indices: Vec<u64> = picked.into_iter().collect();
+  indices.sort_unstable();
+  indices
+}
+
+fn addresses(intervals: &[(u32, u32)], indices: impl Iterator<Item = u64>, n: u64) -> Vec<Ipv4Addr> {
+  // walk the intervals once, mapping sorted indices to addresses
+  let mut out = Vec::with_capacity(n as usize);
+  let mut offset = 0;
+  let mut intervals = intervals.iter();
+  let mut cur = intervals.next();
+  for i in indices {
+    while let Some(&(a, b)) = cur {
+      let len = (b - a) as u64 + 1;
+      if i < offset + len {
+        out.push(Ipv4Addr::from(a + (i - offset) as u32));
+        break;
+      }
+      offset += len;
+      cur = intervals.next();
+    }
+  }
+  out
+}
+
+fn sample_blocks(intervals: &[(u32, u32)], prefix: u8, k: u64, seed: u64) -> Result<Vec<Ipv4Addr>, IpRangeError> {
+  if prefix > 32 {
+    return Err(IpRangeError::InvalidPrefix(prefix));
+  }
+  let mask = mask_from_prefix(prefix);
+  let mut out = Vec::new();
+  let mut group: Vec<(u32, u32)> = Vec::new(); // parts of the current block
+
+  // every block gets its own seed, so its sample does not depend on the others
+  let flush = |group: &mut Vec<(u32, u32)>, out: &mut Vec<Ipv4Addr>| {
+    if let Some(&(start, _)) = group.first() {
+      out.extend(sample_intervals(group, k, seed ^ splitmix64((start & mask) as u64)));
+      group.clear();
+    }
+  };
+
+  // clip the intervals on block boundaries, and sample each block on its own
+  for &(a, b) in intervals {
+    let mut start = a;
+    loop {
+      let block = start & mask;
+      let end = b.min(block | !mask);
+      if group.first().is_some_and(|&(g, _)| g & mask != block) {
+        flush(&mut group, &mut out);
+      }
+      group.push((start, end));
+
+      if end == b {
+        break;
+      }
+      start = end + 1;
+    }
+  }
+  flush(&mut group, &mut out);
+  Ok(out)
+}
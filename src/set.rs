@@ -0,0 +1,415 @@
+use std::fmt;
+use std::net::Ipv4Addr;
+
+use crate::alias::Resolving;
+use crate::{full_match, Aliases, IpPattern, IpRange, IpRangeError, ParseOptions, ParseWarning, Rule};
+
+// sorted, disjoint and non adjacent intervals of IPv4 addresses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpRangeSet {
+  intervals: Vec<(u32, u32)>,
+}
+
+// result of parsing a list of ranges
+#[derive(Debug)]
+pub struct ParsedSet {
+  pub set: IpRangeSet, // every valid item, with exclusions subtracted
+  pub errors: Vec<ItemError>, // items that failed to parse, in input order
+  pub warnings: Vec<ItemWarning>, // suspicious but valid items, see IpRange::warnings
+}
+
+#[derive(Debug)]
+pub struct ItemError {
+  pub item: String, // the offending item, without its exclusion marker
+  pub offset: usize, // byte offset of the item in the input
+  pub error: IpRangeError,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemWarning {
+  pub item: String, // the suspicious item, without its exclusion marker
+  pub offset: usize, // byte offset of the item in the input
+  pub warning: ParseWarning,
+}
+
+// an item that is neither an alias nor braces, see IpRangeSet::parse_operand
+pub(crate) enum Operand {
+  Range(IpRange),
+  Pattern(IpRangeSet),
+}
+
+
+// ===== core logic =====
+
+
+impl IpRangeSet {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /*
+  parse a comma or whitespace separated list of ranges
+
+  every item may use any notation IpRange::parse supports, or braces
+  as in IpRange::expand. items prefixed with "!" or "-" are excluded
+  from the result, regardless of where they appear in the list, ex.
+  "10.0.0.0/24, 10.0.1.5-10.0.1.9, !10.0.0.1"
+
+  sparse wildcard patterns, ex. 10.*.0.1, are not a single range and
+  add one range per run instead, 256 for 10.*.0.1 or 10.*.0.*. mind
+  patterns like *.*.*.1, which stand for 16 million ranges
+
+  invalid items are skipped and reported with their position, as are
+  the warnings of valid ones, see IpRange::parse_with_warnings
+  */
+
+  pub fn parse(input: &str) -> ParsedSet {
+    Self::parse_list(input, None, &ParseOptions::default(), &mut Resolving::default())
+  }
+
+  pub fn parse_with(input: &str, opts: &ParseOptions) -> ParsedSet {
+    // same as parse, but every item is parsed as in IpRange::parse_with
+    Self::parse_list(input, None, opts, &mut Resolving::default())
+  }
+
+  pub fn parse_with_aliases(input: &str, aliases: &Aliases) -> ParsedSet {
+    // same as parse, but items may also name an alias, ex. "office, !10.1.99.0/24".
+    // items are parsed with the options of the aliases, see Aliases::with_options
+    Self::parse_list(input, Some(aliases), aliases.options(), &mut Resolving::default())
+  }
+
+  pub(crate) fn parse_list(input: &str, aliases: Option<&Aliases>, opts: &ParseOptions, state: &mut Resolving) -> ParsedSet {
+    let mut include = IpRangeSet::new();
+    let mut exclude = IpRangeSet::new();
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (offset, item, excluded) in split_list(input) {
+      let mut warn = |found: Vec<ParseWarning>| {
+        warnings.extend(found.into_iter().map(|warning| ItemWarning { item: item.to_string(), offset, warning }));
+      };
+      let target = if excluded { &mut exclude } else { &mut include };
+      let parsed = if full_match(Rule::alias_name, item) {
+        match aliases {
+          Some(aliases) => aliases.resolve_inner(item, state).map(|set| *target = target.union(&set)),
+          None => Err(IpRangeError::UndefinedAlias(item.to_string())),
+        }
+      } else if item.contains('{') {
+        IpRange::expand_with(item, opts).map(|ranges| {
+          for r in ranges {
+            warn(r.warnings());
+            target.insert(&r);
+          }
+        })
+      } else {
+        Self::parse_operand(item, opts).map(|operand| match operand {
+          // a plain range is inserted in place, a pattern is merged at once
+          Operand::Range(r) => {
+            warn(r.warnings());
+            target.insert(&r);
+          }
+          Operand::Pattern(set) => *target = target.union(&set),
+        })
+      };
+
+      if let Err(error) = parsed {
+        errors.push(ItemError { item: item.to_string(), offset, error });
+        // a definition fails on its first error, the rest would be resolved for nothing
+        if state.nested() {
+          break;
+        }
+      }
+    }
+
+    ParsedSet { set: include.difference(&exclude), errors, warnings }
+  }
+
+  pub(crate) fn parse_operand(item: &str, opts: &ParseOptions) -> Result<Operand, IpRangeError> {
+    // a single range, or every run of a sparse wildcard pattern
+    match IpRange::parse_with(item, opts) {
+      Err(IpRangeError::NonContiguous(s)) => match IpPattern::parse_with(item, opts) {
+        Ok(pattern) => Ok(Operand::Pattern(IpRangeSet::from(&pattern))),
+        Err(_) => Err(IpRangeError::NonContiguous(s)),
+      },
+      r => r.map(Operand::Range),
+    }
+  }
+
+  pub(crate) fn intervals(&self) -> &[(u32, u32)] {
+    &self.intervals
+  }
+
+  pub fn insert(&mut self, range: &IpRange) {
+    // add every address of the range to the set
+    self.insert_interval(u32::from(range.first), u32::from(range.last));
+  }
+
+  pub fn remove(&mut self, range: &IpRange) {
+    // remove every address of the range from the set
+    self.remove_interval(u32::from(range.first), u32::from(range.last));
+  }
+
+  pub fn union(&self, other: &IpRangeSet) -> IpRangeSet {
+    // addresses in either set
+    let mut all = [self.intervals.as_slice(), other.intervals.as_slice()].concat();
+    all.sort_unstable();
+    Self::from_sorted(all)
+  }
+
+  pub fn intersection(&self, other: &IpRangeSet) -> IpRangeSet {
+    // addresses in both sets
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < self.intervals.len() && j < other.intervals.len() {
+      let (a, b) = self.intervals[i];
+      let (x, y) = other.intervals[j];
+      if a.max(x) <= b.min(y) {
+        out.push((a.max(x), b.min(y)));
+      }
+
+      // drop whichever interval ends first
+      if b < y { i += 1; } else { j += 1; }
+    }
+
+    IpRangeSet { intervals: out }
+  }
+
+  pub fn symmetric_difference(&self, other: &IpRangeSet) -> IpRangeSet {
+    // addresses in exactly one of the sets
+    self.difference(other).union(&other.difference(self))
+  }
+
+  pub fn complement(&self) -> IpRangeSet {
+    // every IPv4 address not in the set
+    let mut out = Vec::with_capacity(self.intervals.len() + 1);
+    let mut next: u64 = 0;
+
+    for &(a, b) in &self.intervals {
+      if (a as u64) > next {
+        out.push((next as u32, a - 1));
+      }
+      next = b as u64 + 1;
+    }
+    if next <= u32::MAX as u64 {
+      out.push((next as u32, u32::MAX));
+    }
+
+    IpRangeSet { intervals: out }
+  }
+
+  pub fn difference(&self, other: &IpRangeSet) -> IpRangeSet {
+    // addresses in this set but not in the other
+    let mut out = Vec::new();
+    let mut j = 0;
+
+    for &(a, b) in &self.intervals {
+      let mut a = a as u64;
+      // skip intervals of the other set that end before this one
+      while j < other.intervals.len() && (other.intervals[j].1 as u64) < a {
+        j += 1;
+      }
+
+      let mut k = j;
+      while k < other.intervals.len() && other.intervals[k].0 <= b {
+        let (x, y) = other.intervals[k];
+        if (x as u64) > a {
+          out.push((a as u32, x - 1));
+        }
+        a = y as u64 + 1;
+        k += 1;
+      }
+
+      if a <= b as u64 {
+        out.push((a as u32, b));
+      }
+    }
+
+    IpRangeSet { intervals: out }
+  }
+
+  pub fn contains(&self, ip: Ipv4Addr) -> bool {
+    // boolean check for whether any interval contains the address
+    let x = u32::from(ip);
+    let i = self.intervals.partition_point(|&(_, b)| b < x);
+    self.intervals.get(i).is_some_and(|&(a, _)| a <= x)
+  }
+
+  pub fn size(&self) -> u128 {
+    // number of IPv4 addresses covered by the set
+    self.intervals.iter().map(|&(a, b)| (b - a) as u128 + 1).sum()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.intervals.is_empty()
+  }
+
+  pub fn ranges(&self) -> impl DoubleEndedIterator<Item = IpRange> + '_ {
+    // the normalized ranges of the set, in ascending order
+    self.intervals.iter().map(|&(a, b)| IpRange::canonical(a, b))
+  }
+
+  fn from_sorted(intervals: Vec<(u32, u32)>) -> Self {
+    // normalize intervals sorted by their start
+    let mut out: Vec<(u32, u32)> = Vec::with_capacity(intervals.len());
+    for (a, b) in intervals {
+      match out.last_mut() {
+        Some(last) if a as u64 <= last.1 as u64 + 1 => last.1 = last.1.max(b),
+        _ => out.push((a, b)),
+      }
+    }
+    IpRangeSet { intervals: out }
+  }
+
+  fn insert_interval(&mut self, a: u32, b: u32) {
+    // merge [a, b] with every interval it overlaps or touches
+    let lo = self.intervals.partition_point(|&(_, y)| (y as u64) + 1 < a as u64);
+    let hi = self.intervals.partition_point(|&(x, _)| x as u64 <= b as u64 + 1);
+
+    let (mut a, mut b) = (a, b);
+    if lo < hi {
+      a = a.min(self.intervals[lo].0);
+      b = b.max(self.intervals[hi - 1].1);
+    }
+    self.intervals.splice(lo..hi, [(a, b)]);
+  }
+
+  fn remove_interval(&mut self, a: u32, b: u32) {
+    // cut [a, b] out of every interval it overlaps, keeping what sticks out
+    let lo = self.intervals.partition_point(|&(_, y)| y < a);
+    let hi = self.intervals.partition_point(|&(x, _)| x <= b);
+    if lo >= hi {
+      return;
+    }
+
+    let mut keep = Vec::with_capacity(2);
+    if self.intervals[lo].0 < a {
+      keep.push((self.intervals[lo].0, a - 1));
+    }
+    if self.intervals[hi - 1].1 > b {
+      keep.push((b + 1, self.intervals[hi - 1].1));
+    }
+    self.intervals.splice(lo..hi, keep);
+  }
+}
+
+
+// ===== list parsing helpers =====
+
+
+pub(crate) fn split_list(input: &str) -> Vec<(usize, &str, bool)> {
+  // split on commas and whitespace outside braces, into (offset, item, excluded)
+  let mut items = Vec::new();
+  let mut depth = 0;
+  let mut start = None;
+  let mut excluded = false;
+
+  let mut push = |start: usize, end: usize, excluded: &mut bool| {
+    let mut item = &input[start..end];
+    let mut offset = start;
+    if let Some(rest) = item.strip_prefix(['!', '-']) {
+      *excluded = true;
+      item = rest;
+      offset += 1;
+    }
+
+    // a lone marker excludes the next item, ex. "! 10.0.0.1"
+    if !item.is_empty() {
+      items.push((offset, item, *excluded));
+      *excluded = false;
+    }
+  };
+
+  for (i, c) in input.char_indices() {
+    match c {
+      '{' => depth += 1,
+      '}' => depth -= 1,
+      ',' if depth == 0 => {
+        if let Some(s) = start.take() { push(s, i, &mut excluded); }
+        continue;
+      }
+      c if c.is_whitespace() && depth == 0 => {
+        if let Some(s) = start.take() { push(s, i, &mut excluded); }
+        continue;
+      }
+      _ => {}
+    }
+    start.get_or_insert(i);
+  }
+  if let Some(s) = start {
+    push(s, input.len(), &mut excluded);
+  }
+
+  items
+}
+
+
+// ===== type conversions =====
+
+
+impl fmt::Display for ItemError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} (at {}): {}", self.item, self.offset, self.error)
+  }
+}
+
+impl fmt::Display for ItemWarning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} (at {}): {}", self.item, self.offset, self.warning)
+  }
+}
+
+
+impl FromIterator<IpRange> for IpRangeSet {
+  fn from_iter<I: IntoIterator<Item = IpRange>>(iter: I) -> Self {
+    // sort once instead of inserting one by one
+    let mut intervals: Vec<(u32, u32)> = iter.into_iter().map(|r| (u32::from(r.first), u32::from(r.last))).collect();
+    intervals.sort_unstable();
+    Self::from_sorted(intervals)
+  }
+}
+
+impl<'a> FromIterator<&'a IpRange> for IpRangeSet {
+  fn from_iter<I: IntoIterator<Item = &'a IpRange>>(iter: I) -> Self {
+    let mut intervals: Vec<(u32, u32)> = iter.into_iter().map(|r| (u32::from(r.first), u32::from(r.last))).collect();
+    intervals.sort_unstable();
+    Self::from_sorted(intervals)
+  }
+}
+
+impl Extend<IpRange> for IpRangeSet {
+  fn extend<I: IntoIterator<Item = IpRange>>(&mut self, iter: I) {
+    for r in iter {
+      self.insert(&r);
+    }
+  }
+}
+
+impl<'a> Extend<&'a IpRange> for IpRangeSet {
+  fn extend<I: IntoIterator<Item = &'a IpRange>>(&mut self, iter: I) {
+    for r in iter {
+      self.insert(r);
+    }
+  }
+}
+
+impl From<IpRange> for IpRangeSet {
+  fn from(range: IpRange) -> Self {
+    let mut set = IpRangeSet::new();
+    set.insert(&range);
+    set
+  }
+}
+
+impl From<&IpPattern> for IpRangeSet {
+  fn from(pattern: &IpPattern) -> Self {
+    // one interval per run, trailing wildcards widen a run instead of starting new ones
+    let trailing = pattern.octets.iter().rev().take_while(|o| o.is_none()).count();
+    let span = ((1u64 << (8 * trailing)) - 1) as u32;
+
+    let mut octets = pattern.octets;
+    octets[4 - trailing..].fill(Some(0));
+    let starts = IpPattern { input: pattern.input.clone(), octets };
+    Self::from_sorted(starts.iter().map(|ip| (u32::from(ip), u32::from(ip) + span)).collect())
+  }
+}
@@ -0,0 +1,129 @@
+use std::iter::FusedIterator;
+use std::net::Ipv4Addr;
+
+use crate::{IpRange, IpRangeError, IpRangeSet, ShuffledIter};
+
+// how addresses are dealt out to shards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardStrategy {
+  Contiguous, // shard i gets the i-th block of consecutive addresses
+  Interleaved, // shard i gets every n-th address, starting at the i-th
+  Shuffled(u64) // shard i gets every n-th address of the seeded iter_shuffled order
+}
+
+/*
+one shard of a range or set, an iterator over its addresses
+
+the n shards of the same input and strategy are disjoint, and together
+visit every address exactly once
+*/
+
+#[derive(Debug, Clone)]
+pub struct Shard {
+  blocks: Vec<(u64, u32)>, // index of the first address of every interval, and that address
+  order: ShardOrder,
+}
+
+#[derive(Debug, Clone)]
+enum ShardOrder {
+  Stride { next: u64, end: u64, stride: u64 }, // indices next, next + stride, ... below end
+  Shuffled(ShuffledIter),
+}
+
+
+// ===== core logic =====
+
+
+impl IpRange {
+  pub fn shard(&self, index: u64, count: u64) -> Result<Shard, IpRangeError> {
+    // contiguous shard, see shard_with
+    self.shard_with(index, count, ShardStrategy::Contiguous)
+  }
+
+  pub fn shard_with(&self, index: u64, count: u64, strategy: ShardStrategy) -> Result<Shard, IpRangeError> {
+    Shard::new(vec![(0, u32::from(self.first))], self.size as u64, index, count, strategy)
+  }
+}
+
+impl IpRangeSet {
+  pub fn shard(&self, index: u64, count: u64) -> Result<Shard, IpRangeError> {
+    // contiguous shard, see shard_with
+    self.shard_with(index, count, ShardStrategy::Contiguous)
+  }
+
+  pub fn shard_with(&self, index: u64, count: u64, strategy: ShardStrategy) -> Result<Shard, IpRangeError> {
+    // the set is sharded as if its ranges were laid end to end
+    let mut blocks = Vec::with_capacity(self.intervals().len());
+    let mut size = 0;
+    for &(a, b) in self.intervals() {
+      blocks.push((size, a));
+      size += (b - a) as u64 + 1;
+    }
+    Shard::new(blocks, size, index, count, strategy)
+  }
+}
+
+impl Shard {
+  fn new(blocks: Vec<(u64, u32)>, size: u64, index: u64, count: u64, strategy: ShardStrategy) -> Result<Self, IpRangeError> {
+    if index >= count {
+      return Err(IpRangeError::InvalidShard { index, count });
+    }
+
+    let order = match strategy {
+      ShardStrategy::Contiguous => {
+        // block boundaries at size * i / count, sizes differ by at most one
+        let bound = |i: u64| (size as u128 * i as u128 / count as u128) as u64;
+        ShardOrder::Stride { next: bound(index), end: bound(index + 1), stride: 1 }
+      }
+      ShardStrategy::Interleaved => ShardOrder::Stride { next: index, end: size, stride: count },
+      ShardStrategy::Shuffled(seed) => ShardOrder::Shuffled(ShuffledIter::new(0, size, seed).interleave(index, count)),
+    };
+
+    Ok(Shard { blocks, order })
+  }
+
+  fn addr_at(&self, index: u64) -> Ipv4Addr {
+    // address at index within the concatenated intervals
+    let block = self.blocks.partition_point(|&(start, _)| start <= index) - 1;
+    let (start, first) = self.blocks[block];
+    Ipv4Addr::from(first + (index - start) as u32)
+  }
+}
+
+
+// ===== iterators =====
+
+
+impl Iterator for Shard {
+  type Item = Ipv4Addr;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let index = match &mut self.order {
+      ShardOrder::Stride { next, end, stride } => {
+        if *next >= *end {
+          return None;
+        }
+        let index = *next;
+        *next = next.saturating_add(*stride);
+        index
+      }
+      ShardOrder::Shuffled(it) => it.next_index()?,
+    };
+    Some(self.addr_at(index))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    match &self.order {
+      ShardOrder::Stride { next, end, stride } => {
+        let left = end.saturating_sub(*next).div_ceil(*stride);
+        match usize::try_from(left) {
+          Ok(n) => (n, Some(n)),
+          Err(_) => (usize::MAX, None),
+        }
+      }
+      ShardOrder::Shuffled(it) => it.size_hint(),
+    }
+  }
+}
+
+impl FusedIterator for Shard {}
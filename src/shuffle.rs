@@ -0,0 +1,188 @@
+use std::iter::FusedIterator;
+use std::net::Ipv4Addr;
+
+use crate::IpRange;
+
+/*
+visits every address of a range exactly once in a pseudo random order,
+with constant memory, as zmap does
+
+the multiplicative group modulo a prime p > size is cyclic, so repeatedly
+multiplying by a primitive root g walks every value in 1..p exactly once.
+value x stands for the address at index x - 1, values past the end of the
+range are skipped. the seed picks both g and the starting point
+*/
+
+#[derive(Debug, Clone)]
+pub struct ShuffledIter {
+  first: u32, // first address of the range
+  size: u64, // number of addresses
+  prime: u64,
+  generator: u64,
+  start: u64, // group element the walk starts on
+  cur: u64, // next group element to visit
+  position: u64, // group elements visited so far, at most prime - 1
+  stride: u64, // positions advanced per step, more than 1 when sharded
+  step: u64, // generator ^ stride
+}
+
+
+// ===== core logic =====
+
+
+impl IpRange {
+  pub fn iter_shuffled(&self, seed: u64) -> ShuffledIter {
+    // deterministic for a given seed, see ShuffledIter
+    ShuffledIter::new(u32::from(self.first), self.size as u64, seed)
+  }
+}
+
+impl ShuffledIter {
+  pub(crate) fn new(first: u32, size: u64, seed: u64) -> Self {
+    let prime = next_prime(size + 1);
+    let order = prime - 1;
+
+    let mut generator = 1 + splitmix64(seed) % order;
+    while !is_primitive_root(generator, prime) {
+      generator = generator % order + 1;
+    }
+    let start = pow_mod(generator, splitmix64(seed ^ 0x5851_f42d_4c95_7f2d) % order, prime);
+
+    ShuffledIter { first, size, prime, generator, start, cur: start, position: 0, stride: 1, step: generator }
+  }
+
+  pub(crate) fn interleave(mut self, offset: u64, stride: u64) -> Self {
+    // visit only the positions offset, offset + stride, ..., the shards of one walk
+    self.stride = stride;
+    self.step = pow_mod(self.generator, stride, self.prime);
+    self.resume_at(offset)
+  }
+
+  pub(crate) fn next_index(&mut self) -> Option<u64> {
+    // index of the next address within the range
+    while self.position < self.prime - 1 {
+      let index = self.cur - 1;
+      self.cur = mul_mod(self.cur, self.step, self.prime);
+      self.position = self.position.saturating_add(self.stride);
+
+      if index < self.size {
+        return Some(index);
+      }
+    }
+    None
+  }
+
+  pub fn position(&self) -> u64 {
+    // cursor to save, resume_at continues from it
+    self.position
+  }
+
+  pub fn resume_at(mut self, position: u64) -> Self {
+    // skip ahead to a saved position in O(log n), given the same range and seed
+    self.position = position.min(self.prime - 1);
+    self.cur = mul_mod(self.start, pow_mod(self.generator, self.position, self.prime), self.prime);
+    self
+  }
+}
+
+pub(crate) fn splitmix64(seed: u64) -> u64 {
+  let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+  z ^ (z >> 31)
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+  ((a as u128 * b as u128) % m as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+  let mut acc = 1 % m;
+  base %= m;
+  while exp > 0 {
+    if exp & 1 == 1 {
+      acc = mul_mod(acc, base, m);
+    }
+    base = mul_mod(base, base, m);
+    exp >>= 1;
+  }
+  acc
+}
+
+fn is_prime(n: u64) -> bool {
+  // deterministic miller rabin, these bases cover every u64
+  if n < 2 {
+    return false;
+  }
+  const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+  for p in BASES {
+    if n.is_multiple_of(p) {
+      return n == p;
+    }
+  }
+
+  let d = (n - 1) >> (n - 1).trailing_zeros();
+  let s = (n - 1).trailing_zeros();
+  'bases: for a in BASES {
+    let mut x = pow_mod(a, d, n);
+    if x == 1 || x == n - 1 {
+      continue;
+    }
+    for _ in 1..s {
+      x = mul_mod(x, x, n);
+      if x == n - 1 {
+        continue 'bases;
+      }
+    }
+    return false;
+  }
+  true
+}
+
+fn next_prime(n: u64) -> u64 {
+  // smallest prime >= n
+  let mut p = n.max(2);
+  while !is_prime(p) {
+    p += 1;
+  }
+  p
+}
+
+fn is_primitive_root(g: u64, prime: u64) -> bool {
+  // g generates the whole group when g^(order/q) != 1 for every prime factor q of the order
+  let order = prime - 1;
+  let mut rest = order;
+  let mut q = 2;
+  while q * q <= rest {
+    if rest.is_multiple_of(q) {
+      if pow_mod(g, order / q, prime) == 1 {
+        return false;
+      }
+      while rest.is_multiple_of(q) {
+        rest /= q;
+      }
+    }
+    q += 1;
+  }
+  rest == 1 || pow_mod(g, order / rest, prime) != 1
+}
+
+
+// ===== iterators =====
+
+
+impl Iterator for ShuffledIter {
+  type Item = Ipv4Addr;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next_index().map(|index| Ipv4Addr::from(self.first + index as u32))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    // values past the end of the range are skipped, so only an upper bound is known
+    let left = (self.prime - 1).saturating_sub(self.position).div_ceil(self.stride);
+    (0, usize::try_from(left).ok())
+  }
+}
+
+impl FusedIterator for ShuffledIter {}
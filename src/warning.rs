@@ -0,0 +1,80 @@
+use std::fmt;
+use std::net::Ipv4Addr;
+
+use crate::{IpRange, IpRangeError, ParseOptions, RangeKind};
+
+// enumerating more addresses than a /8 is probably a mistake
+const HUGE_ENUMERATION: u128 = 1 << 24;
+
+// valid input that is probably a mistake
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+  HostBitsSet { base: Ipv4Addr, network: Ipv4Addr, prefix: u8 }, // ex. 10.0.0.5/24, masked to 10.0.0.0/24
+  CoversEverything, // ex. 0.0.0.0/0 or 0.0.0.0-255.255.255.255
+  HugeEnumeration { size: u128 }, // more addresses than a /8, ex. *
+  StartsOnBroadcast { start: Ipv4Addr } // hyphen range starting on a .255, ex. 10.0.0.255-10.0.1.9
+}
+
+// a parsed range and the warnings raised along the way
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed {
+  pub range: IpRange,
+  pub warnings: Vec<ParseWarning>,
+}
+
+
+// ===== core logic =====
+
+
+impl IpRange {
+  pub fn parse_with_warnings(input: &str, opts: &ParseOptions) -> Result<Parsed, IpRangeError> {
+    // same as parse_with, but also reports suspicious input
+    let range = Self::parse_with(input, opts)?;
+    let warnings = range.warnings();
+    Ok(Parsed { range, warnings })
+  }
+
+  pub fn warnings(&self) -> Vec<ParseWarning> {
+    let mut warnings = Vec::new();
+
+    match self.kind {
+      RangeKind::Cidr { base, prefix } if Some(base) != self.network => {
+        let network = self.network.expect("cidr ranges have a network");
+        warnings.push(ParseWarning::HostBitsSet { base, network, prefix });
+      }
+      RangeKind::Slice { from: network, cidr: Some((base, prefix)), .. } if base != network => {
+        warnings.push(ParseWarning::HostBitsSet { base, network, prefix });
+      }
+      RangeKind::Hyphen { start, end } if start.octets()[3] == 255 && start != end => {
+        warnings.push(ParseWarning::StartsOnBroadcast { start });
+      }
+      _ => {}
+    }
+
+    // the whole space is also a huge enumeration, report the more specific one
+    if self.size == 1 << 32 {
+      warnings.push(ParseWarning::CoversEverything);
+    } else if self.size > HUGE_ENUMERATION {
+      warnings.push(ParseWarning::HugeEnumeration { size: self.size });
+    }
+
+    warnings
+  }
+}
+
+
+// ===== type conversions =====
+
+
+impl fmt::Display for ParseWarning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParseWarning::HostBitsSet { base, network, prefix } => {
+        write!(f, "host bits set in {base}/{prefix}, using {network}/{prefix}")
+      }
+      ParseWarning::CoversEverything => f.write_str("range covers the entire address space, 4294967296 addresses"),
+      ParseWarning::HugeEnumeration { size } => write!(f, "range enumerates {size} addresses"),
+      ParseWarning::StartsOnBroadcast { start } => write!(f, "range starts on a broadcast address, {start}"),
+    }
+  }
+}
@@ -0,0 +1,88 @@
+use std::net::Ipv4Addr;
+use netdec::{Aliases, IpRangeError, IpRangeSet};
+
+// ===== named range alias tests =====
+
+const CATALOG: &str = "
+# shared network objects
+office = 10.1.0.0/16
+vpn = 10.8.*   # remote access
+trusted = office, vpn, !10.1.99.0/24
+
+everyone = trusted 192.168.0.0/24
+";
+
+#[test]
+fn alias_resolution() {
+  let aliases = Aliases::parse(CATALOG).unwrap();
+  assert_eq!(aliases.get("vpn"), Some("10.8.*"));
+
+  let trusted = aliases.resolve("trusted").unwrap();
+  assert_eq!(trusted.size(), 65536 * 2 - 256);
+  assert!(trusted.contains(Ipv4Addr::new(10, 8, 1, 1)));
+  assert!(!trusted.contains(Ipv4Addr::new(10, 1, 99, 1)));
+
+  let everyone = aliases.resolve("everyone").unwrap();
+  assert_eq!(everyone.size(), trusted.size() + 256);
+}
+
+#[test]
+fn alias_in_lists_and_expressions() {
+  let aliases = Aliases::parse(CATALOG).unwrap();
+
+  let parsed = IpRangeSet::parse_with_aliases("office, !10.1.0.0/17", &aliases);
+  assert!(parsed.errors.is_empty());
+  assert_eq!(parsed.set.size(), 32768);
+
+  let set = IpRangeSet::eval_with_aliases("(trusted - vpn) & 10.1.98.0/23", &aliases).unwrap();
+  assert_eq!(set.size(), 256);
+
+  // without a catalog, names are undefined
+  let err = IpRangeSet::eval("office | vpn").unwrap_err();
+  assert!(matches!(err, IpRangeError::UndefinedAlias(n) if n == "office"));
+  let parsed = IpRangeSet::parse("office");
+  assert!(matches!(&parsed.errors[0].error, IpRangeError::UndefinedAlias(n) if n == "office"));
+}
+
+#[test]
+fn alias_errors() {
+  let aliases = Aliases::parse("a = b\nb = 10.0.0.0/8, c\nc = a").unwrap();
+  match aliases.resolve("a").unwrap_err() {
+    IpRangeError::AliasCycle(path) => assert_eq!(path, ["a", "b", "c", "a"]),
+    e => panic!("expected a cycle, got {e}"),
+  }
+
+  let aliases = Aliases::parse("a = 10.0.0.0/8, missing").unwrap();
+  let err = aliases.resolve("a").unwrap_err();
+  assert!(matches!(err, IpRangeError::UndefinedAlias(n) if n == "missing"));
+
+  let aliases = Aliases::parse("a = 10.0.0.300").unwrap();
+  let err = aliases.resolve("a").unwrap_err();
+  assert!(matches!(err, IpRangeError::InAlias { name, .. } if name == "a"));
+
+  for (text, line) in [("a = 1.1.1.1\nnope", 2), ("1a = 1.1.1.1", 1), ("a = 1.1.1.1\n\na = 2.2.2.2", 3)] {
+    let err = Aliases::parse(text).unwrap_err();
+    assert!(matches!(err, IpRangeError::AliasSyntax { line: l, .. } if l == line), "wrong error for {text:?}");
+  }
+}
+
+#[test]
+fn deep_diamonds_resolve_once() {
+  // a0 = a1, b1 and b0 = a1, b1, and so on, 2^60 paths without memoization
+  let mut aliases = Aliases::new();
+  for i in 0..60 {
+    let value = format!("a{0}, b{0}, 10.0.{i}.0/24", i + 1);
+    aliases.define(&format!("a{i}"), &value);
+    aliases.define(&format!("b{i}"), &value);
+  }
+  aliases.define("a60", "192.168.0.1");
+  aliases.define("b60", "192.168.0.2");
+
+  let set = aliases.resolve("a0").unwrap();
+  assert_eq!(set.size(), 60 * 256 + 2);
+  assert_eq!(IpRangeSet::parse_with_aliases("a0, !b30", &aliases).set.size(), 30 * 256);
+
+  // cycles are still reported through shared aliases
+  aliases.define("b59", "a0");
+  assert!(matches!(aliases.resolve("a0"), Err(IpRangeError::AliasCycle(_))));
+}
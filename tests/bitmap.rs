@@ -0,0 +1,150 @@
+#![cfg(feature = "bitmap")]
+
+use std::net::Ipv4Addr;
+use netdec::{AddressBitmap, IpRange, IpRangeError, IpRangeSet};
+
+mod common;
+use common::parse;
+
+// ===== address bitmap tests =====
+
+fn ip(s: &str) -> Ipv4Addr {
+  s.parse().unwrap()
+}
+
+#[test]
+fn insert_remove_contains() {
+  let mut b = AddressBitmap::new();
+  assert!(b.is_empty());
+  assert!(b.insert(ip("10.0.0.1")));
+  assert!(!b.insert(ip("10.0.0.1")));
+  assert!(b.insert(ip("192.168.1.9")));
+  assert_eq!(b.size(), 2);
+  assert!(b.contains(ip("10.0.0.1")));
+  assert!(!b.contains(ip("10.0.0.2")));
+
+  assert!(b.remove(ip("10.0.0.1")));
+  assert!(!b.remove(ip("10.0.0.1")));
+  assert_eq!(b.size(), 1);
+  assert_eq!(b.first(), Some(ip("192.168.1.9")));
+}
+
+#[test]
+fn ranges_in_and_out() {
+  let mut b = AddressBitmap::new();
+  assert_eq!(b.insert_range(&parse("10.0.0.0/24")), 256);
+  assert_eq!(b.insert_range(&parse("10.0.0.250-10.0.1.4")), 5);
+  assert!(b.contains_range(&parse("10.0.0.0-10.0.1.4")));
+  assert_eq!(b.remove_range(&parse("10.0.0.16/28")), 16);
+  assert!(!b.contains_range(&parse("10.0.0.0/24")));
+
+  let runs: Vec<String> = b.ranges().map(|r| r.to_string()).collect();
+  assert_eq!(runs, ["10.0.0.0/28", "10.0.0.32-10.0.1.4"]);
+
+  // the kind names the notation of the input
+  for r in b.ranges() {
+    assert_eq!(parse(&r.input).kind, r.kind, "{}", r.input);
+  }
+}
+
+#[test]
+fn whole_address_space() {
+  let b = AddressBitmap::from(&parse("0.0.0.0/0"));
+  assert_eq!(b.size(), 1 << 32);
+  assert_eq!(b.first(), Some(Ipv4Addr::UNSPECIFIED));
+  assert_eq!(b.last(), Some(Ipv4Addr::BROADCAST));
+  assert!(b.complement().is_empty());
+  assert_eq!(b.select(u32::MAX as u64), Some(Ipv4Addr::BROADCAST));
+  assert_eq!(b.select(1 << 32), None);
+}
+
+#[test]
+fn rank_and_select() {
+  let b: AddressBitmap = ["10.0.0.5", "10.0.0.9", "172.16.0.1", "192.168.0.1"].into_iter().map(ip).collect();
+  assert_eq!(b.rank(ip("10.0.0.4")), 0);
+  assert_eq!(b.rank(ip("10.0.0.5")), 1);
+  assert_eq!(b.rank(ip("100.0.0.0")), 2);
+  assert_eq!(b.rank(ip("255.255.255.255")), 4);
+
+  assert_eq!(b.select(0), Some(ip("10.0.0.5")));
+  assert_eq!(b.select(3), Some(ip("192.168.0.1")));
+  assert_eq!(b.select(4), None);
+  for addr in b.iter() {
+    assert_eq!(b.select(b.rank(addr) - 1), Some(addr));
+  }
+}
+
+#[test]
+fn set_operations_match_interval_sets() {
+  let a = IpRangeSet::parse("10.0.0.0/24 10.0.2.7 172.16.0.0-172.16.1.9").set;
+  let b = IpRangeSet::parse("10.0.0.128/25 10.0.2.0/24 172.16.1.0/24").set;
+  let (ba, bb) = (AddressBitmap::from(&a), AddressBitmap::from(&b));
+
+  assert_eq!(ba.union(&bb).to_set(), a.union(&b));
+  assert_eq!(ba.intersection(&bb).to_set(), a.intersection(&b));
+  assert_eq!(ba.difference(&bb).to_set(), a.difference(&b));
+  assert_eq!(ba.symmetric_difference(&bb).to_set(), a.symmetric_difference(&b));
+  assert_eq!(ba.complement().to_set(), a.complement());
+}
+
+#[test]
+fn conversions_round_trip() {
+  let set = IpRangeSet::parse("10.0.0.0/8 !10.1.0.0/16 192.168.0.1 192.168.0.3").set;
+  let b = AddressBitmap::from(&set);
+  assert_eq!(b.size(), set.size());
+  assert_eq!(IpRangeSet::from(&b), set);
+
+  let ranges: Vec<IpRange> = set.ranges().collect();
+  let from_ranges: AddressBitmap = ranges.iter().collect();
+  assert_eq!(from_ranges, b);
+  assert_eq!(b.ranges().collect::<Vec<_>>(), ranges);
+
+  let mut extended = AddressBitmap::new();
+  extended.extend(ranges);
+  extended.extend([ip("1.2.3.4")]);
+  assert_eq!(extended.size(), b.size() + 1);
+}
+
+#[test]
+fn serialization_round_trip() {
+  let mut b = AddressBitmap::from(&parse("10.0.0.0/16"));
+  b.extend((0..1000u32).map(|i| Ipv4Addr::from(0xc0a8_0000 + i * 7)));
+
+  let mut buf = Vec::new();
+  b.write_to(&mut buf).unwrap();
+  assert_eq!(buf.len(), b.serialized_size());
+  // the /16 is a single run, far smaller than 65536 addresses
+  assert!(buf.len() < 4096, "{}", buf.len());
+  assert_eq!(AddressBitmap::from_reader(buf.as_slice()).unwrap(), b);
+
+  let path = std::env::temp_dir().join(format!("netdec-bitmap-{}.bin", std::process::id()));
+  b.save(&path).unwrap();
+  let loaded = AddressBitmap::load(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(loaded, b);
+}
+
+#[test]
+fn serialized_runs_stay_small() {
+  // runs built by range and set operations are written run compressed
+  let mut b = AddressBitmap::from(&parse("10.0.1.0-10.0.200.0"));
+  b.insert(ip("10.0.0.1"));
+  assert!(b.serialized_size() < 64, "{}", b.serialized_size());
+
+  let b = AddressBitmap::from(&parse("10.0.0.0/8")).difference(&AddressBitmap::from(&parse("10.5.0.0/16")));
+  let mut buf = Vec::new();
+  b.write_to(&mut buf).unwrap();
+  assert_eq!(buf.len(), b.serialized_size());
+  // a small run container per /16, the floor of the portable format
+  assert!(buf.len() <= 256 * 16, "{}", buf.len());
+  assert_eq!(AddressBitmap::from_reader(buf.as_slice()).unwrap(), b);
+}
+
+#[test]
+fn corrupt_input_is_an_io_error() {
+  let mut buf = Vec::new();
+  AddressBitmap::from(&parse("10.0.0.0/24")).write_to(&mut buf).unwrap();
+  buf.truncate(buf.len() / 2);
+  assert!(matches!(AddressBitmap::from_reader(buf.as_slice()), Err(IpRangeError::Io(_))));
+  assert!(matches!(AddressBitmap::from_reader(&b"nope"[..]), Err(IpRangeError::Io(_))));
+}
@@ -0,0 +1,48 @@
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeError, IpRangeSet};
+
+// ===== brace expansion tests =====
+
+fn expanded(input: &str) -> Vec<String> {
+  IpRange::expand(input).unwrap().map(|r| r.input).collect()
+}
+
+#[test]
+fn brace_lists_and_sequences() {
+  assert_eq!(expanded("192.168.1.{10,20,30}"), ["192.168.1.10", "192.168.1.20", "192.168.1.30"]);
+  assert_eq!(expanded("10.{0,2}.{1..3}.0/24"), [
+    "10.0.1.0/24", "10.0.2.0/24", "10.0.3.0/24",
+    "10.2.1.0/24", "10.2.2.0/24", "10.2.3.0/24",
+  ]);
+  assert_eq!(expanded("10.0.0.0/{8,16..17}"), ["10.0.0.0/8", "10.0.0.0/16", "10.0.0.0/17"]);
+  assert_eq!(expanded("10.{3..1}.*"), ["10.3.*", "10.2.*", "10.1.*"]);
+
+  // input without braces expands to itself
+  assert_eq!(expanded("10.0.0.0/8"), ["10.0.0.0/8"]);
+}
+
+#[test]
+fn brace_merged_set() {
+  let set: IpRangeSet = IpRange::expand("10.0.0.{0..3,8}").unwrap().collect();
+  assert_eq!(set.size(), 5);
+  assert_eq!(set.ranges().count(), 2);
+  assert!(set.contains(Ipv4Addr::new(10, 0, 0, 2)));
+  assert!(!set.contains(Ipv4Addr::new(10, 0, 0, 4)));
+
+  let blocks: IpRangeSet = IpRange::expand("10.0.{0..255}.0/24").unwrap().collect();
+  let merged: Vec<IpRange> = blocks.ranges().collect();
+  assert_eq!(merged.len(), 1);
+  assert_eq!(merged[0].prefix, Some(16));
+}
+
+#[test]
+fn brace_invalid() {
+  for s in ["10.{0,300}.0.0", "10.0.0.0/{24,33}", "10.{1..256}.*"] {
+    let err = IpRange::expand(s).err().unwrap();
+    assert!(matches!(err, IpRangeError::InvalidBrace(_)), "expected brace error for {s}");
+  }
+
+  for s in ["10.{}.0.0", "10.{1,}.0.0", "10.{a}.0.0", "10.{1..}.0.0", "10.0.0.0-10.0.0.{1,2}"] {
+    assert!(IpRange::expand(s).is_err(), "should reject {s}");
+  }
+}
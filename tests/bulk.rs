@@ -0,0 +1,60 @@
+use std::io::{BufReader, Cursor};
+use std::net::Ipv4Addr;
+use netdec::{parse_bulk, parse_bulk_reader, stream_bulk, BulkEntry, IpRangeError};
+
+// ===== bulk line parsing tests =====
+
+const LIST: &str = "\u{feff}# blocklist\r\n10.0.0.0/24\r\n\n  10.0.1.5-10.0.1.9 ; scanner\nnope\n10.*\n10.0.0.9-10.0.0.1\n";
+
+fn lines(entries: &[BulkEntry]) -> Vec<(usize, bool)> {
+  entries.iter().map(|e| (e.line, e.result.is_ok())).collect()
+}
+
+#[test]
+fn parse_buffer() {
+  let entries = parse_bulk(LIST.as_bytes());
+  assert_eq!(lines(&entries), [(2, true), (4, true), (5, false), (6, true), (7, false)]);
+
+  let r = entries[1].result.as_ref().unwrap();
+  assert_eq!((r.first, r.last), (Ipv4Addr::new(10, 0, 1, 5), Ipv4Addr::new(10, 0, 1, 9)));
+  assert!(matches!(entries[2].result, Err(IpRangeError::Syntax(_))));
+  assert!(matches!(entries[4].result, Err(IpRangeError::InvalidRangeOrder)));
+}
+
+#[test]
+fn parse_reader() {
+  let entries = parse_bulk_reader(Cursor::new(LIST)).unwrap();
+  assert_eq!(lines(&entries), lines(&parse_bulk(LIST.as_bytes())));
+}
+
+#[test]
+fn invalid_utf8_is_a_line_error() {
+  let entries = parse_bulk(b"10.0.0.0/8\n10.0.\xff.0\n10.0.0.1\n");
+  assert_eq!(lines(&entries), [(1, true), (2, false), (3, true)]);
+}
+
+#[test]
+fn large_input_keeps_order() {
+  let input: String = (0..20_000u32).map(|i| format!("{}\n", Ipv4Addr::from(i * 7))).collect();
+  let entries = parse_bulk(input.as_bytes());
+  assert_eq!(entries.len(), 20_000);
+  for (i, e) in entries.iter().enumerate() {
+    assert_eq!(e.line, i + 1);
+    assert_eq!(e.result.as_ref().unwrap().first, Ipv4Addr::from(i as u32 * 7));
+  }
+}
+
+#[test]
+fn stream_matches_buffer() {
+  let expected = lines(&parse_bulk(LIST.as_bytes()));
+  for chunk in [1, 2, 3, 100] {
+    let streamed: Vec<BulkEntry> = stream_bulk(BufReader::new(LIST.as_bytes()), chunk).map(Result::unwrap).collect();
+    assert_eq!(lines(&streamed), expected, "chunk of {chunk} lines");
+  }
+}
+
+#[test]
+fn stream_without_trailing_newline() {
+  let streamed: Vec<BulkEntry> = stream_bulk(Cursor::new("10.0.0.0/8\n10.1.0.0/16"), 1).map(Result::unwrap).collect();
+  assert_eq!(lines(&streamed), [(1, true), (2, true)]);
+}
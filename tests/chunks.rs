@@ -0,0 +1,88 @@
+use std::net::Ipv4Addr;
+use netdec::IpRange;
+
+mod common;
+use common::parse;
+
+// ===== stride, chunk and block iterator tests =====
+
+fn inputs(ranges: impl Iterator<Item = IpRange>) -> Vec<String> {
+  ranges.map(|r| r.input).collect()
+}
+
+#[test]
+fn step() {
+  let r = parse("10.0.0.0-10.0.0.10");
+  let got: Vec<Ipv4Addr> = r.iter_step(4).collect();
+  assert_eq!(got, [Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 4), Ipv4Addr::new(10, 0, 0, 8)]);
+
+  let back: Vec<Ipv4Addr> = r.iter_step(4).rev().collect();
+  assert_eq!(back, [Ipv4Addr::new(10, 0, 0, 8), Ipv4Addr::new(10, 0, 0, 4), Ipv4Addr::new(10, 0, 0, 0)]);
+
+  assert_eq!(r.iter_step(1).collect::<Vec<_>>(), r.iter().collect::<Vec<_>>());
+  assert_eq!(r.iter_step(100).collect::<Vec<_>>(), [Ipv4Addr::new(10, 0, 0, 0)]);
+}
+
+#[test]
+fn step_whole_space() {
+  let all = parse("*");
+  let mut it = all.iter_step(1 << 8);
+  assert_eq!(it.len(), 1 << 24);
+  assert_eq!(it.next_back(), Some(Ipv4Addr::new(255, 255, 255, 0)));
+  assert_eq!(it.nth(1), Some(Ipv4Addr::new(0, 0, 1, 0)));
+}
+
+#[test]
+#[should_panic]
+fn step_zero() {
+  parse("10.0.0.0/24").iter_step(0);
+}
+
+#[test]
+fn chunks() {
+  let r = parse("10.0.0.0-10.0.0.9");
+  assert_eq!(inputs(r.chunks(4)), ["10.0.0.0/30", "10.0.0.4/30", "10.0.0.8/31"]);
+  assert_eq!(inputs(r.chunks(4).rev()), ["10.0.0.8/31", "10.0.0.4/30", "10.0.0.0/30"]);
+  assert_eq!(inputs(r.chunks(3)), ["10.0.0.0-10.0.0.2", "10.0.0.3-10.0.0.5", "10.0.0.6-10.0.0.8", "10.0.0.9/32"]);
+  for c in r.chunks(3).chain(r.blocks(29)) {
+    assert_eq!(parse(&c.input).kind, c.kind, "{}", c.input);
+  }
+  assert_eq!(r.chunks(100).len(), 1);
+
+  let total: u128 = parse("10.0.0.0/8").chunks(1000).map(|c| c.size).sum();
+  assert_eq!(total, 1 << 24);
+}
+
+#[test]
+fn chunks_larger_than_range() {
+  let r = parse("255.255.255.0-255.255.255.255");
+  assert_eq!(inputs(r.chunks(u64::MAX)), ["255.255.255.0/24"]);
+  assert_eq!(inputs(r.chunks(u64::MAX).rev()), ["255.255.255.0/24"]);
+  assert_eq!(inputs(parse("*").chunks(u64::MAX)), ["0.0.0.0/0"]);
+}
+
+#[test]
+fn blocks_of_unaligned_range() {
+  let r = parse("10.0.0.5-10.0.2.9");
+  assert_eq!(inputs(r.blocks(24)), ["10.0.0.5-10.0.0.255", "10.0.1.0/24", "10.0.2.0-10.0.2.9"]);
+  assert_eq!(inputs(r.blocks(24).rev()), ["10.0.2.0-10.0.2.9", "10.0.1.0/24", "10.0.0.5-10.0.0.255"]);
+  assert_eq!(inputs(r.blocks(16)), ["10.0.0.5-10.0.2.9"]);
+  assert_eq!(r.blocks(32).len(), r.size as usize);
+}
+
+#[test]
+fn blocks_of_aligned_range() {
+  let r = parse("10.0.0.0/22");
+  assert_eq!(inputs(r.blocks(24)), ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/24", "10.0.3.0/24"]);
+  assert_eq!(inputs(parse("*").blocks(0)), ["0.0.0.0/0"]);
+  assert_eq!(parse("*").blocks(8).len(), 256);
+}
+
+#[test]
+fn lazy_and_double_ended() {
+  let mut it = parse("10.0.0.0/8").blocks(24);
+  assert_eq!(it.len(), 1 << 16);
+  assert_eq!(it.nth(1000).unwrap().input, "10.3.232.0/24");
+  assert_eq!(it.nth_back(0).unwrap().input, "10.255.255.0/24");
+  assert_eq!(it.len(), (1 << 16) - 1002);
+}
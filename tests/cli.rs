@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// ===== command line option tests =====
+
+fn netdec(args: &[&str]) -> (i32, String, String) {
+  // exit code, stdout and stderr of the binary
+  let out = Command::new(env!("CARGO_BIN_EXE_netdec")).args(args).output().unwrap();
+  let text = |b: Vec<u8>| String::from_utf8(b).unwrap();
+  (out.status.code().unwrap(), text(out.stdout), text(out.stderr))
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+  let path = std::env::temp_dir().join(format!("netdec-cli-{}-{name}", std::process::id()));
+  fs::write(&path, contents).unwrap();
+  path
+}
+
+#[test]
+fn options_apply_to_expressions() {
+  let (code, _, err) = netdec(&["--reject-host-bits", "--expr", "10.0.0.5/24 | 1.2.3.4"]);
+  assert_eq!(code, 2);
+  assert!(err.contains("host bits set"), "{err}");
+
+  let (code, out, _) = netdec(&["--zero-padded", "--expr", "010.000.000.000/024 - 10.0.0.0/25"]);
+  assert_eq!(code, 0);
+  assert!(out.contains("total: 128 addresses"), "{out}");
+}
+
+#[test]
+fn options_apply_to_list_files() {
+  let path = temp_file("list.txt", "10.0.0.0+4\n10.0.1.0/24\n");
+  let (code, _, err) = netdec(&["--disable", "count", "--file", path.to_str().unwrap()]);
+  fs::remove_file(&path).unwrap();
+  assert_eq!(code, 2);
+  assert!(err.contains("count notation is disabled"), "{err}");
+}
+
+#[test]
+fn options_apply_to_aliases() {
+  let path = temp_file("aliases.txt", "office01 = 10.0.0.0\n");
+  let aliases = path.to_str().unwrap();
+  let (code, out, _) = netdec(&["--classful", "--aliases", aliases, "office01"]);
+  let (expr_code, expr_out, _) = netdec(&["--classful", "--aliases", aliases, "--expr", "office01 - 10.1.0.0/16"]);
+  fs::remove_file(&path).unwrap();
+
+  assert_eq!(code, 0);
+  assert!(out.contains("total: 16777216 addresses"), "{out}");
+  assert_eq!(expr_code, 0);
+  assert!(expr_out.contains("total: 16711680 addresses"), "{expr_out}");
+}
+
+#[test]
+fn options_apply_to_lists_and_braces() {
+  let (code, _, err) = netdec(&["--reject-host-bits", "10.0.0.0/24", "10.0.1.5/24"]);
+  assert_eq!(code, 2);
+  assert!(err.contains("host bits set"), "{err}");
+
+  let (code, _, err) = netdec(&["--reject-host-bits", "10.0.0.{0,5}/24"]);
+  assert_eq!(code, 2);
+  assert!(err.contains("host bits set"), "{err}");
+}
+
+#[test]
+fn address_mode_flag() {
+  let (code, out, _) = netdec(&["--address-mode", "lenient", "0x0a000001-10.1.0.9"]);
+  assert_eq!(code, 0);
+  assert!(out.contains("first: 10.0.0.1"), "{out}");
+
+  let (code, _, err) = netdec(&["--address-mode", "strict", "--expr", "10.0.0.0/8 - 10.1"]);
+  assert_eq!(code, 2);
+  assert!(err.contains("ambiguous"), "{err}");
+
+  let (code, _, _) = netdec(&["--address-mode", "sloppy", "10.0.0.1"]);
+  assert_eq!(code, 2);
+}
+
+#[test]
+fn sparse_patterns_in_lists() {
+  // a single pattern is described, in a list it adds every run
+  let (code, out, _) = netdec(&["10.*.0.1"]);
+  assert_eq!(code, 0);
+  assert!(out.contains("kind: pattern"), "{out}");
+
+  let (code, out, _) = netdec(&["10.*.0.1", "10.0.0.0/31"]);
+  assert_eq!(code, 0);
+  assert!(out.contains("total: 257 addresses in 256 ranges"), "{out}");
+
+  let (code, out, _) = netdec(&["--expr", "10.*.0.1 - 10.128.0.0/9"]);
+  assert_eq!(code, 0);
+  assert!(out.contains("total: 128 addresses in 128 ranges"), "{out}");
+}
+
+#[test]
+fn failed_items_print_nothing() {
+  let (code, out, err) = netdec(&["10.0.0.0/24", "10.0.0.300", "10.0.1.0/24"]);
+  assert_eq!(code, 2);
+  assert!(out.is_empty(), "{out}");
+  assert!(err.contains("10.0.0.300"), "{err}");
+
+  let list = temp_file("failed_items.txt", "10.0.0.0/24\nbogus\n");
+  let (code, out, err) = netdec(&["--file", list.to_str().unwrap()]);
+  assert_eq!(code, 2);
+  assert!(out.is_empty(), "{out}");
+  assert!(err.contains("failed_items.txt:2:1"), "{err}");
+}
+
+#[test]
+fn deny_warnings_on_every_path() {
+  let list = temp_file("warnings.txt", "10.0.0.0/24\n10.0.0.255-10.0.1.3\n");
+  let aliases = temp_file("warnings_aliases.txt", "office = 10.1.0.0/16\n");
+  let cases: [&[&str]; 5] = [
+    &["10.0.0.5/24"],
+    &["10.0.0.0/24", "10.0.1.5/24"],
+    &["--expr", "0.0.0.0/0 - 10.0.0.0/8"],
+    &["--file", list.to_str().unwrap()],
+    &["--aliases", aliases.to_str().unwrap(), "office", "*"],
+  ];
+
+  for args in cases {
+    let (code, out, err) = netdec(args);
+    assert_eq!(code, 0, "{args:?}");
+    assert!(!out.is_empty(), "{args:?}");
+    assert!(err.contains("warning: "), "{args:?}: {err}");
+
+    let (code, out, err) = netdec(&[&["--deny-warnings"], args].concat());
+    assert_eq!(code, 2, "{args:?}");
+    assert!(out.is_empty(), "{args:?}: {out}");
+    assert!(err.contains("denied by --deny-warnings"), "{args:?}: {err}");
+  }
+
+  let (code, _, err) = netdec(&["--deny-warnings", "10.0.{0,1}.5/24"]);
+  assert_eq!(code, 2);
+  assert!(err.contains("10.0.1.5/24: host bits set"), "{err}");
+}
+
+#[test]
+fn sample_honors_parse_flags() {
+  let (code, out, _) = netdec(&["sample", "--zero-padded", "-n", "3", "010.0.0.0/24"]);
+  assert_eq!(code, 0);
+  assert!(out.lines().all(|l| l.starts_with("10.0.0.")), "{out}");
+  assert_eq!(out.lines().count(), 3);
+
+  let (code, out, err) = netdec(&["sample", "--reject-host-bits", "10.0.0.5/24"]);
+  assert_eq!(code, 2);
+  assert!(out.is_empty(), "{out}");
+  assert!(err.contains("host bits set"), "{err}");
+
+  let (code, _, err) = netdec(&["sample", "--deny-warnings", "*"]);
+  assert_eq!(code, 2);
+  assert!(err.contains("denied by --deny-warnings"), "{err}");
+}
+
+#[test]
+fn single_pattern_honors_options() {
+  let (code, out, _) = netdec(&["--zero-padded", "010.*.000.1"]);
+  assert_eq!(code, 0);
+  assert!(out.contains("kind: pattern"), "{out}");
+
+  let (code, _, _) = netdec(&["--disable", "wildcard", "10.*.0.1"]);
+  assert_eq!(code, 2);
+}
+
+#[test]
+fn expr_rejects_extra_ranges() {
+  let (code, out, err) = netdec(&["--expr", "10.0.0.0/8", "10.0.0.1"]);
+  assert_eq!(code, 2);
+  assert!(out.is_empty(), "{out}");
+  assert!(err.contains("--expr takes no other ranges"), "{err}");
+
+  let list = temp_file("expr_extra.txt", "10.0.0.0/24\n");
+  let (code, _, _) = netdec(&["--file", list.to_str().unwrap(), "--expr", "10.0.0.0/8"]);
+  assert_eq!(code, 2);
+}
+
+#[test]
+fn list_files_use_aliases() {
+  let aliases = temp_file("list_aliases.txt", "office = 10.1.0.0/16\n");
+  let list = temp_file("list_with_aliases.txt", "office\n10.*.0.1\n");
+  let (code, out, err) = netdec(&["--aliases", aliases.to_str().unwrap(), "--file", list.to_str().unwrap()]);
+  let (plain_code, _, plain_err) = netdec(&["--file", list.to_str().unwrap()]);
+  fs::remove_file(&aliases).unwrap();
+  fs::remove_file(&list).unwrap();
+
+  assert_eq!(code, 0, "{err}");
+  assert!(out.contains(&format!("total: {} addresses", 65536 + 255)), "{out}");
+  assert_eq!(plain_code, 2);
+  assert!(plain_err.contains("office"), "{plain_err}");
+}
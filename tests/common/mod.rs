@@ -0,0 +1,15 @@
+#![allow(dead_code)] // every test crate uses a different subset
+
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeSet};
+
+// ===== shared test helpers =====
+
+pub fn parse(s: &str) -> IpRange {
+  s.parse().unwrap()
+}
+
+pub fn bounds(set: &IpRangeSet) -> Vec<(Ipv4Addr, Ipv4Addr)> {
+  // first and last address of every range in the set
+  set.ranges().map(|r| (r.first, r.last)).collect()
+}
@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+use netdec::{CompactRange, IpRange, IpRangeError, RangeKind};
+
+// ===== compact range tests =====
+
+const INPUTS: &[&str] = &[
+  "10.0.0.0/24", "10.0.0.5/24", "0.0.0.0/0", "1.2.3.4/32",
+  "10.0.0.1-10.0.0.9", "0.0.0.0-255.255.255.255",
+  "*", "10.*", "10.0.*", "10.0.0.*", "10.*.*.*", "10.0.*.*",
+  "10.1.2.3", "10.0.0.0+256", "255.255.255.255+1",
+  "10.0.0.0/24[10:20]", "10.0.0.0/24[-5:]", "10.*[:3]", "10.0.0.1-10.0.0.9[1:-1]",
+];
+
+#[test]
+fn matches_ip_range_parse() {
+  for input in INPUTS {
+    let compact = CompactRange::parse(input).unwrap();
+    let full = IpRange::parse(input).unwrap();
+    assert_eq!((compact.first, compact.last, compact.kind), (full.first, full.last, full.kind), "{input}");
+    assert_eq!(compact.size(), full.size, "{input}");
+  }
+}
+
+#[test]
+fn details() {
+  let r = CompactRange::parse("10.0.0.0-10.0.0.255").unwrap();
+  let d = r.details();
+  assert_eq!(d.input, "10.0.0.0-10.0.0.255");
+  assert_eq!(d.prefix, Some(24));
+  assert_eq!(d.netmask, Some(Ipv4Addr::new(255, 255, 255, 0)));
+  assert_eq!(d.kind, r.kind);
+  assert_eq!(IpRange::from(r), d);
+  assert_eq!(CompactRange::from(&d), r);
+
+  // the input reads back as the same notation
+  for input in INPUTS {
+    let d = CompactRange::parse(input).unwrap().details();
+    let again = IpRange::parse(&d.input).unwrap();
+    assert_eq!((again.first, again.last, again.kind), (d.first, d.last, d.kind), "{input} -> {}", d.input);
+  }
+}
+
+#[test]
+fn is_copy() {
+  let r: CompactRange = "10.0.0.0/8".parse().unwrap();
+  let copy = r;
+  assert_eq!(r, copy);
+  assert!(r.contains(Ipv4Addr::new(10, 1, 2, 3)));
+  assert_eq!(r.to_string(), "10.0.0.0/8");
+}
+
+#[test]
+fn errors() {
+  assert!(matches!(CompactRange::parse("10.0.0.9-10.0.0.1"), Err(IpRangeError::InvalidRangeOrder)));
+  assert!(matches!(CompactRange::parse("10.0.0.0+0"), Err(IpRangeError::EmptyRange)));
+  assert!(matches!(CompactRange::parse("255.255.255.255+2"), Err(IpRangeError::CountOverflow(_))));
+  assert!(matches!(CompactRange::parse("10.*.0.1"), Err(IpRangeError::NonContiguous(_))));
+  assert!(matches!(CompactRange::parse("10.0.0.0/24[5:5]"), Err(IpRangeError::EmptyRange)));
+  assert!(matches!(CompactRange::parse("10.0.0.256"), Err(IpRangeError::Syntax(_))));
+  assert!(matches!(CompactRange::parse("10.0.0.1[0:1]"), Err(IpRangeError::Syntax(_))));
+}
+
+#[test]
+fn kinds() {
+  assert_eq!(CompactRange::parse("10.*").unwrap().kind, RangeKind::Wildcard { specified_octets: 1 });
+  assert_eq!(CompactRange::parse("10.0.*.*").unwrap().kind, RangeKind::Wildcard { specified_octets: 2 });
+}
+
+#[test]
+fn equality_ignores_notation() {
+  let cidr = CompactRange::parse("10.0.0.0/24").unwrap();
+  let hyphen = CompactRange::parse("10.0.0.0-10.0.0.255").unwrap();
+  assert_eq!(cidr, hyphen);
+  assert_eq!(HashSet::from([cidr, hyphen]).len(), 1);
+
+  let mut sorted = ["10.0.0.0/8", "1.2.3.4", "10.0.0.0/24", "10.*"].map(|s| CompactRange::parse(s).unwrap());
+  sorted.sort();
+  assert_eq!(sorted.map(|r| r.to_string()), ["1.2.3.4/32", "10.0.0.0/24", "10.0.0.0/8", "10.0.0.0/8"]);
+}
+
+#[test]
+fn classful_details() {
+  let classful = IpRange::parse_classful("172.16.5.1").unwrap();
+  let d = CompactRange::from(&classful).details();
+  assert_eq!(d.input, "172.16.0.0/16");
+  assert_eq!(IpRange::parse(&d.input).unwrap(), classful);
+}
@@ -0,0 +1,39 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use netdec::CompactRange;
+
+// ===== compact range allocation tests =====
+
+// counts every allocation made by this test binary
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+    unsafe { System.alloc(layout) }
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    unsafe { System.dealloc(ptr, layout) }
+  }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[test]
+fn parse_does_not_allocate() {
+  let inputs = ["10.0.0.0/24", "10.0.0.1-10.0.0.9", "10.*", "10.0.*.*", "10.1.2.3", "10.0.0.0+256", "10.0.0.0/24[1:-1]"];
+
+  let before = ALLOCATIONS.load(Ordering::SeqCst);
+  let mut total = 0u128;
+  for input in inputs {
+    total += CompactRange::parse(input).unwrap().size();
+  }
+  let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+  assert_eq!(after, before);
+  assert!(total > 0);
+}
@@ -0,0 +1,73 @@
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeError, IpRangeSet, RangeKind};
+
+// ===== typed constructor tests =====
+
+#[test]
+fn cidr() {
+  let r = IpRange::cidr(Ipv4Addr::new(10, 0, 0, 0), 24).unwrap();
+  assert_eq!(r.input, "10.0.0.0/24");
+  assert_eq!(r.last, Ipv4Addr::new(10, 0, 0, 255));
+  assert_eq!(r.prefix, Some(24));
+  assert_eq!(r.size, 256);
+  assert_eq!(r.kind, RangeKind::Cidr { base: Ipv4Addr::new(10, 0, 0, 0), prefix: 24 });
+
+  // host bits are masked as in parse
+  let r = IpRange::cidr(Ipv4Addr::new(10, 0, 0, 5), 24).unwrap();
+  assert_eq!(r.first, Ipv4Addr::new(10, 0, 0, 0));
+  assert_eq!(r.input, "10.0.0.0/24");
+
+  assert_eq!(IpRange::cidr(Ipv4Addr::UNSPECIFIED, 0).unwrap().size, 1 << 32);
+  assert!(matches!(IpRange::cidr(Ipv4Addr::UNSPECIFIED, 33), Err(IpRangeError::InvalidPrefix(33))));
+}
+
+#[test]
+fn cidr_matches_parse() {
+  for p in 0..=32 {
+    let built = IpRange::cidr(Ipv4Addr::new(192, 168, 7, 9), p).unwrap();
+    let parsed: IpRange = format!("192.168.7.9/{p}").parse().unwrap();
+    assert_eq!((built.first, built.last, built.prefix), (parsed.first, parsed.last, parsed.prefix));
+  }
+}
+
+#[test]
+fn span() {
+  let r = IpRange::span(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 9)).unwrap();
+  assert_eq!(r.input, "10.0.0.1-10.0.0.9");
+  assert_eq!(r.size, 9);
+  assert_eq!(r.prefix, None);
+
+  assert_eq!(r.kind, RangeKind::Hyphen { start: Ipv4Addr::new(10, 0, 0, 1), end: Ipv4Addr::new(10, 0, 0, 9) });
+
+  // aligned spans get a cidr form
+  let r = IpRange::span(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 1, 255)).unwrap();
+  assert_eq!(r.input, "10.0.0.0/23");
+  assert_eq!(r.kind, RangeKind::Cidr { base: Ipv4Addr::new(10, 0, 0, 0), prefix: 23 });
+
+  let err = IpRange::span(Ipv4Addr::new(10, 0, 0, 9), Ipv4Addr::new(10, 0, 0, 1));
+  assert!(matches!(err, Err(IpRangeError::InvalidRangeOrder)));
+}
+
+#[test]
+fn single() {
+  let r = IpRange::single(Ipv4Addr::new(10, 1, 2, 3));
+  assert_eq!(r.input, "10.1.2.3/32");
+  assert_eq!(r.kind, RangeKind::Cidr { base: Ipv4Addr::new(10, 1, 2, 3), prefix: 32 });
+  assert_eq!(r.size, 1);
+}
+
+#[test]
+fn try_from_range_inclusive() {
+  let r = IpRange::try_from(Ipv4Addr::new(10, 0, 0, 1)..=Ipv4Addr::new(10, 0, 0, 4)).unwrap();
+  assert_eq!(r.size, 4);
+
+  let err = IpRange::try_from(Ipv4Addr::new(10, 0, 0, 4)..=Ipv4Addr::new(10, 0, 0, 1));
+  assert!(matches!(err, Err(IpRangeError::InvalidRangeOrder)));
+}
+
+#[test]
+fn set_ranges_are_canonical() {
+  let set = IpRangeSet::parse("10.0.0.0/24, 10.0.1.5-10.0.1.9").set;
+  let inputs: Vec<String> = set.ranges().map(|r| r.input).collect();
+  assert_eq!(inputs, ["10.0.0.0/24", "10.0.1.5-10.0.1.9"]);
+}
@@ -0,0 +1,67 @@
+use netdec::{Diagnostic, DiagnosticCode, IpRange, IpRangeError, IpRangeSet};
+
+// ===== parse diagnostic tests =====
+
+fn diagnostic(input: &str) -> Diagnostic {
+  match IpRange::parse(input).unwrap_err() {
+    IpRangeError::Syntax(d) => *d,
+    e => panic!("expected a diagnostic for {input:?}, got {e:?}"),
+  }
+}
+
+#[test]
+fn diagnostic_codes_and_spans() {
+  for (input, code, span) in [
+    ("192.168.0.256/24", DiagnosticCode::OctetOutOfRange, 10..13),
+    ("10.0.0.0/33", DiagnosticCode::PrefixOutOfRange, 9..11),
+    ("", DiagnosticCode::EmptyInput, 0..0),
+    (" 10.0.0.1", DiagnosticCode::UnexpectedWhitespace, 0..1),
+    ("10..0.1", DiagnosticCode::EmptyOctet, 3..4),
+    ("01.2.3.4", DiagnosticCode::LeadingZero, 0..2),
+    ("1.2.3", DiagnosticCode::MissingOctets, 0..5),
+    ("1.2.3.4.5", DiagnosticCode::TooManyOctets, 0..9),
+    ("10.0.0.0/", DiagnosticCode::MissingPrefix, 8..9),
+    ("10.0.0.1-10.0.0.999", DiagnosticCode::OctetOutOfRange, 16..19),
+    ("10.0.0.1+", DiagnosticCode::MissingCount, 8..9),
+    ("10.0.0.1?", DiagnosticCode::InvalidCharacter, 8..9),
+    ("10.0.0.0/24[5", DiagnosticCode::UnexpectedToken, 11..12),
+  ] {
+    let d = diagnostic(input);
+    assert_eq!(d.code, code, "code mismatch for {input:?}");
+    assert_eq!(d.span, span, "span mismatch for {input:?}");
+    assert_eq!(d.input, input);
+  }
+}
+
+#[test]
+fn diagnostic_hints() {
+  assert_eq!(diagnostic("192.168.0.256/24").hint.as_deref(), Some("octet 256 exceeds 255"));
+  assert_eq!(diagnostic("10.0.0.0/33").hint.as_deref(), Some("prefix must be 0–32, found 33"));
+  assert_eq!(diagnostic("010.0.0.1").hint.as_deref(), Some("write 10 instead of 010"));
+  assert_eq!(DiagnosticCode::OctetOutOfRange.as_str(), "octet-out-of-range");
+
+  let err = IpRange::parse("192.168.0.256/24").unwrap_err();
+  assert_eq!(err.to_string(), "octet out of range: octet 256 exceeds 255");
+}
+
+#[test]
+fn diagnostic_render() {
+  let rendered = diagnostic("192.168.0.256/24").render();
+  assert_eq!(rendered, "\
+error[octet-out-of-range]: octet out of range
+  192.168.0.256/24
+            ^^^
+  hint: octet 256 exceeds 255
+");
+}
+
+#[test]
+fn diagnostic_expressions() {
+  match IpRangeSet::eval("10.0.0.0/8 | | 1.1.1.1").unwrap_err() {
+    IpRangeError::Syntax(d) => {
+      assert_eq!(d.code, DiagnosticCode::UnexpectedToken);
+      assert_eq!(d.span, 13..14);
+    }
+    e => panic!("expected a diagnostic, got {e:?}"),
+  }
+}
@@ -0,0 +1,44 @@
+mod common;
+use common::parse;
+
+// ===== canonical display tests =====
+
+#[test]
+fn canonical_display() {
+  assert_eq!(parse("10.0.0.5/24").to_string(), "10.0.0.0/24");
+  assert_eq!(parse("10.0.0.0-10.0.0.255").to_string(), "10.0.0.0/24");
+  assert_eq!(parse("10.*").to_string(), "10.0.0.0/8");
+  assert_eq!(parse("10.0.0.1").to_string(), "10.0.0.1/32");
+  assert_eq!(parse("10.0.0.1-10.0.0.9").to_string(), "10.0.0.1-10.0.0.9");
+  assert_eq!(parse("10.0.0.0+3").to_string(), "10.0.0.0-10.0.0.2");
+  assert_eq!(parse("*").to_string(), "0.0.0.0/0");
+}
+
+#[test]
+fn display_round_trips() {
+  for input in ["10.0.0.5/24", "10.0.0.1-10.0.0.9", "10.*", "*", "10.0.0.1", "10.0.0.0+300", "10.0.0.0/24[10:20]", "10.*.*.*"] {
+    let r = parse(input);
+    let again = parse(&r.to_string());
+    assert_eq!((again.first, again.last), (r.first, r.last), "{input}");
+    assert_eq!(again.to_string(), r.to_string(), "{input}");
+  }
+}
+
+#[test]
+fn best_notation() {
+  assert_eq!(parse("10.0.0.0-10.0.0.255").to_best_notation(), "10.0.0.*");
+  assert_eq!(parse("10.0.0.0/8").to_best_notation(), "10.*");
+  assert_eq!(parse("0.0.0.0/0").to_best_notation(), "*");
+  assert_eq!(parse("10.0.0.0/25").to_best_notation(), "10.0.0.0/25");
+  assert_eq!(parse("10.0.0.1/32").to_best_notation(), "10.0.0.1");
+  assert_eq!(parse("10.0.0.1-10.0.0.9").to_best_notation(), "10.0.0.1-10.0.0.9");
+}
+
+#[test]
+fn best_notation_round_trips() {
+  for input in ["10.0.0.0/23", "10.0.0.1-10.0.0.9", "172.16.*", "1.2.3.4", "10.0.0.0+256"] {
+    let r = parse(input);
+    let again = parse(&r.to_best_notation());
+    assert_eq!((again.first, again.last), (r.first, r.last), "{input}");
+  }
+}
@@ -0,0 +1,75 @@
+use std::net::Ipv4Addr;
+use netdec::{IpRangeError, IpRangeSet};
+
+mod common;
+use common::bounds;
+
+// ===== set expression tests =====
+
+#[test]
+fn expr_operators() {
+  let set = IpRangeSet::eval("(10.0.0.0/8 - 10.1.0.0/16) | 192.168.*").unwrap();
+  assert_eq!(bounds(&set), vec![
+    (Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 255, 255)),
+    (Ipv4Addr::new(10, 2, 0, 0), Ipv4Addr::new(10, 255, 255, 255)),
+    (Ipv4Addr::new(192, 168, 0, 0), Ipv4Addr::new(192, 168, 255, 255)),
+  ]);
+
+  let and = IpRangeSet::eval("10.0.0.0/24 & 10.0.0.128-10.0.1.5").unwrap();
+  assert_eq!(bounds(&and), vec![(Ipv4Addr::new(10, 0, 0, 128), Ipv4Addr::new(10, 0, 0, 255))]);
+
+  let xor = IpRangeSet::eval("10.0.0.0/24 ^ 10.0.0.128/24").unwrap();
+  assert_eq!(xor.size(), 0);
+  let xor = IpRangeSet::eval("10.0.0.0/25 ^ 10.0.0.64/26").unwrap();
+  assert_eq!(bounds(&xor), vec![(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 63))]);
+
+  let not = IpRangeSet::eval("~10.0.0.0/8").unwrap();
+  assert_eq!(not.size(), (1u128 << 32) - (1 << 24));
+  assert!(!not.contains(Ipv4Addr::new(10, 1, 2, 3)));
+  assert!(IpRangeSet::eval("~~*").unwrap().size() == 1u128 << 32);
+}
+
+#[test]
+fn expr_precedence() {
+  // & binds tighter than |, so this is 10.0.0.0/24 | (10.0.1.0/24 & 10.0.1.0/25)
+  let set = IpRangeSet::eval("10.0.0.0/24 | 10.0.1.0/24 & 10.0.1.0/25").unwrap();
+  assert_eq!(set.size(), 384);
+
+  // difference is left associative
+  let set = IpRangeSet::eval("10.0.0.0/24 - 10.0.0.0/25 - 10.0.0.128/26").unwrap();
+  assert_eq!(bounds(&set), vec![(Ipv4Addr::new(10, 0, 0, 192), Ipv4Addr::new(10, 0, 0, 255))]);
+
+  // complement binds tightest
+  let set = IpRangeSet::eval("~10.0.0.0/1 & 10.0.0.0/8").unwrap();
+  assert!(set.is_empty());
+}
+
+#[test]
+fn expr_hyphen_vs_difference() {
+  let hyphen = IpRangeSet::eval("10.0.0.1-10.0.0.5").unwrap();
+  assert_eq!(hyphen.size(), 5);
+
+  let diff = IpRangeSet::eval("10.0.0.0/30 - 10.0.0.1").unwrap();
+  assert_eq!(diff.size(), 3);
+}
+
+#[test]
+fn expr_sparse_patterns() {
+  let set = IpRangeSet::eval("10.*.0.* & 10.0.0.0/15").unwrap();
+  assert_eq!(bounds(&set), vec![
+    (Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 255)),
+    (Ipv4Addr::new(10, 1, 0, 0), Ipv4Addr::new(10, 1, 0, 255)),
+  ]);
+  assert_eq!(IpRangeSet::eval("10.0.0.0/8 - 10.*.*.0").unwrap().size(), (1 << 24) - (1 << 16));
+}
+
+#[test]
+fn expr_invalid() {
+  for s in ["", "10.0.0.0/8 |", "(10.0.0.0/8", "10.0.0.0/8 + 1.1.1.1", "10.0.0.0/8 && 1.1.1.1"] {
+    let err = IpRangeSet::eval(s).unwrap_err();
+    assert!(matches!(err, IpRangeError::Syntax(_)), "expected syntax error for {s:?}");
+  }
+
+  let err = IpRangeSet::eval("10.0.0.0/8 | 1.1.1.9-1.1.1.1").unwrap_err();
+  assert!(matches!(err, IpRangeError::InvalidRangeOrder));
+}
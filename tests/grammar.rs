@@ -5,6 +5,11 @@ use netdec::{
   matches_range_cidr,
   matches_range_hyphen,
   matches_range_wildcard,
+  matches_range_count,
+  matches_range_slice,
+  matches_range_single,
+  matches_range_pattern,
+  matches_range_brace,
   matches_range_any
 };
 
@@ -101,11 +106,91 @@ fn wildcard_invalid() {
   }
 }
 
+// ===== count notation tests =====
+
+#[test]
+fn count_valid() {
+  for s in ["10.0.0.0+256", "0.0.0.0+0", "192.168.0.1+4294967296"] {
+    assert!(matches_range_count(s), "should accept {s}");
+  }
+}
+
+#[test]
+fn count_invalid() {
+  for s in ["10.0.0.0+", "10.0.0.0+-1", "10.0.0.0+010", "+5", "10.*+5"] {
+    assert!(!matches_range_count(s), "should reject {s}");
+  }
+}
+
+// ===== slice notation tests =====
+
+#[test]
+fn slice_valid() {
+  for s in ["10.0.0.0/24[10:20]", "10.0.0.0/16[-5:]", "10.*[:3]", "10.0.0.1-10.0.0.9[:]"] {
+    assert!(matches_range_slice(s), "should accept {s}");
+  }
+}
+
+#[test]
+fn slice_invalid() {
+  for s in ["10.0.0.0/24[10]", "10.0.0.0/24[a:b]", "10.0.0.0[1:2]", "10.0.0.0/24[1:2", "10.0.0.0+5[1:2]"] {
+    assert!(!matches_range_slice(s), "should reject {s}");
+  }
+}
+
+// ===== single address tests =====
+
+#[test]
+fn single_valid() {
+  for s in ["0.0.0.0", "10.1.2.3", "255.255.255.255"] {
+    assert!(matches_range_single(s), "should accept {s}");
+  }
+}
+
+#[test]
+fn single_invalid() {
+  for s in ["10.1.2", "10.1.2.3/24", "10.1.2.256", "10.*"] {
+    assert!(!matches_range_single(s), "should reject {s}");
+  }
+}
+
+// ===== wildcard pattern tests =====
+
+#[test]
+fn pattern_valid() {
+  for s in ["10.*.0.1", "*.*.*.1", "192.168.*.*", "*.*.*.*", "10.0.0.1"] {
+    assert!(matches_range_pattern(s), "should accept {s}");
+  }
+}
+
+#[test]
+fn pattern_invalid() {
+  for s in ["10.*.1", "10.*", "10.**.0.1", "10.*.0.256", "*.*.*.*.*"] {
+    assert!(!matches_range_pattern(s), "should reject {s}");
+  }
+}
+
+// ===== brace expansion tests =====
+
+#[test]
+fn brace_valid() {
+  for s in ["10.{0,2,4}.{1..3}.0/24", "192.168.1.{10,20,30}", "10.0.0.0/{8,16}", "10.{1..3}.*", "*", "10.0.0.1"] {
+    assert!(matches_range_brace(s), "should accept {s}");
+  }
+}
+
+#[test]
+fn brace_invalid() {
+  for s in ["10.{}.0.0", "10.{1,}.0.0", "10.{1...3}.0.0", "10.{1,2.0.0", "{1,2}", "10.0.0.{1,2}-10.0.0.9"] {
+    assert!(!matches_range_brace(s), "should reject {s}");
+  }
+}
+
 // ===== general rule tests =====
 
 #[test]
 fn any_valid() {
-  for s in ["192.168.0.0/16", "192.168.0.1-192.168.0.10", "10.*", "10.0.*", "10.0.0.*"] {
+  for s in ["192.168.0.0/16", "192.168.0.1-192.168.0.10", "10.*", "10.0.*", "10.0.0.*", "10.0.0.0+256", "10.0.0.0/24[10:20]", "10.1.2.3", "10.*.0.1"] {
     assert!(matches_range_any(s), "should accept {s}");
   }
 }
@@ -0,0 +1,97 @@
+use std::net::Ipv4Addr;
+use netdec::{parse_ipv4_with, AddressForm, AddressMode, IpRange, IpRangeError, IpRangeSet, ParseOptions};
+
+// ===== legacy inet_aton address form tests =====
+
+#[test]
+fn lenient_forms() {
+  let localhost = Ipv4Addr::new(127, 0, 0, 1);
+  for (s, form) in [
+    ("127.0.0.1", AddressForm::DottedQuad),
+    ("0x7f.1", AddressForm::Legacy { parts: 2, octal: false, hex: true }),
+    ("017700000001", AddressForm::Legacy { parts: 1, octal: true, hex: false }),
+    ("2130706433", AddressForm::Legacy { parts: 1, octal: false, hex: false }),
+    ("127.1", AddressForm::Legacy { parts: 2, octal: false, hex: false }),
+    ("127.0.1", AddressForm::Legacy { parts: 3, octal: false, hex: false }),
+    ("0177.0.0.01", AddressForm::Legacy { parts: 4, octal: true, hex: false }),
+    ("0X7F.0x0.0.0x1", AddressForm::Legacy { parts: 4, octal: false, hex: true }),
+  ] {
+    assert_eq!(parse_ipv4_with(s, AddressMode::Lenient).unwrap(), (localhost, form), "mismatch for {s}");
+  }
+
+  assert_eq!(parse_ipv4_with("10.1", AddressMode::Lenient).unwrap().0, Ipv4Addr::new(10, 0, 0, 1));
+  assert_eq!(parse_ipv4_with("010.0.0.1", AddressMode::Lenient).unwrap().0, Ipv4Addr::new(8, 0, 0, 1));
+  assert_eq!(parse_ipv4_with("0", AddressMode::Lenient).unwrap().0, Ipv4Addr::new(0, 0, 0, 0));
+}
+
+#[test]
+fn lenient_invalid() {
+  for s in ["", "1.2.3.4.5", "256.0.0.1", "1.2.65536", "1.16777216", "4294967296", "08", "0x", "1..2", "+1.2.3.4", "a.b.c.d"] {
+    let err = parse_ipv4_with(s, AddressMode::Lenient).unwrap_err();
+    assert!(matches!(err, IpRangeError::InvalidIp(_)), "should reject {s}");
+  }
+}
+
+#[test]
+fn strict_flags_ambiguity() {
+  assert_eq!(parse_ipv4_with("10.0.0.1", AddressMode::Strict).unwrap().0, Ipv4Addr::new(10, 0, 0, 1));
+
+  for s in ["0x7f.1", "017700000001", "2130706433", "10.1", "010.0.0.1"] {
+    let err = parse_ipv4_with(s, AddressMode::Strict).unwrap_err();
+    assert!(matches!(err, IpRangeError::AmbiguousAddress { .. }), "should flag {s}");
+  }
+
+  // garbage stays plain invalid input
+  let err = parse_ipv4_with("10.0.0.300", AddressMode::Strict).unwrap_err();
+  assert!(matches!(err, IpRangeError::InvalidIp(_)));
+}
+
+// ===== address mode option tests =====
+
+#[test]
+fn address_mode_in_every_notation() {
+  let opts = ParseOptions::new().address_mode(AddressMode::Lenient);
+  for (s, first, last) in [
+    ("10.1", "10.0.0.1", "10.0.0.1"),
+    ("0x0a000000/8", "10.0.0.0", "10.255.255.255"),
+    ("167772160-10.0.1", "10.0.0.0", "10.0.0.1"),
+    ("012.0.0.0+4", "10.0.0.0", "10.0.0.3"),
+    ("10.1/24[1:3]", "10.0.0.1", "10.0.0.2"),
+    ("10.0.0.*", "10.0.0.0", "10.0.0.255"),
+  ] {
+    let r = IpRange::parse_with(s, &opts).unwrap();
+    assert_eq!((r.first, r.last), (first.parse().unwrap(), last.parse().unwrap()), "mismatch for {s}");
+    assert_eq!(r.input, s);
+  }
+
+  // without a mode, legacy forms stay syntax errors
+  assert!(matches!(IpRange::parse("10.1"), Err(IpRangeError::Syntax(_))));
+}
+
+#[test]
+fn address_mode_strict() {
+  let opts = ParseOptions::new().address_mode(AddressMode::Strict);
+  assert!(IpRange::parse_with("10.0.0.0/8", &opts).is_ok());
+  for s in ["10.1", "10.0.0.0-0x0a0000ff", "010.0.0.0/8", "2130706433+2"] {
+    assert!(matches!(IpRange::parse_with(s, &opts), Err(IpRangeError::AmbiguousAddress { .. })), "should flag {s}");
+  }
+
+  // zero padding is stripped first, so the octets stay decimal
+  let r = IpRange::parse_with("010.0.0.1", &opts.zero_padded(true)).unwrap();
+  assert_eq!(r.first, Ipv4Addr::new(10, 0, 0, 1));
+}
+
+#[test]
+fn address_mode_in_lists_and_expressions() {
+  let opts = ParseOptions::new().address_mode(AddressMode::Lenient);
+  let parsed = IpRangeSet::parse_with("10.1, 0x0a000002, !10.1", &opts);
+  assert!(parsed.errors.is_empty());
+  assert_eq!(parsed.set.size(), 1);
+
+  let set = IpRangeSet::eval_with("10.0/16 - 0x0a000000/24 - 10.0.1.0/24[-5:]", &opts).unwrap();
+  assert_eq!(set.size(), 65536 - 256 - 5);
+  assert_eq!(IpRangeSet::eval_with("10.0.0.* | 127.1", &opts).unwrap().size(), 257);
+
+  let strict = ParseOptions::new().address_mode(AddressMode::Strict);
+  assert!(matches!(IpRangeSet::eval_with("10.0.0.0/8 - 10.1", &strict), Err(IpRangeError::AmbiguousAddress { .. })));
+}
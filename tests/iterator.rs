@@ -58,3 +58,70 @@ fn into_iter_conversion() {
   let first_val = r.clone().into_iter().next().unwrap();
   assert_eq!(first_val, Ipv4Addr::new(192,168,1,250));
 }
+
+// ===== exact size and random access tests =====
+
+#[test]
+fn iter_exact_size() {
+  let r: IpRange = "10.0.0.0/8".parse().unwrap();
+  let mut it = r.iter();
+  assert_eq!(it.len(), 1 << 24);
+  it.next();
+  it.next_back();
+  assert_eq!(it.size_hint(), ((1 << 24) - 2, Some((1 << 24) - 2)));
+
+  let all: IpRange = "*".parse().unwrap();
+  assert_eq!(all.iter().len(), 1 << 32);
+}
+
+#[test]
+fn iter_nth_and_skip() {
+  let r: IpRange = "10.0.0.0/8".parse().unwrap();
+  assert_eq!(r.iter().nth(1_000_000), Some(Ipv4Addr::from(0x0a00_0000u32 + 1_000_000)));
+  let skipped: Vec<Ipv4Addr> = r.iter().skip(1_000_000).take(2).collect();
+  assert_eq!(skipped, [Ipv4Addr::from(0x0a00_0000u32 + 1_000_000), Ipv4Addr::from(0x0a00_0000u32 + 1_000_001)]);
+  assert_eq!(r.iter().nth_back(1), Some(Ipv4Addr::new(10, 255, 255, 254)));
+  assert_eq!(r.iter().nth(1 << 24), None);
+
+  let mut it = r.iter();
+  assert_eq!(it.nth((1 << 24) - 1), Some(Ipv4Addr::new(10, 255, 255, 255)));
+  assert_eq!(it.next(), None);
+
+  let mut it = r.iter();
+  it.nth(10);
+  assert_eq!(it.nth_back(0), Some(Ipv4Addr::new(10, 255, 255, 255)));
+  assert_eq!(it.len(), (1 << 24) - 12);
+}
+
+#[test]
+fn iter_count_and_last() {
+  let r: IpRange = "10.0.0.9-10.0.0.12".parse().unwrap();
+  assert_eq!(r.iter().count(), 4);
+  assert_eq!(r.iter().last(), Some(Ipv4Addr::new(10, 0, 0, 12)));
+
+  let mut it = r.iter();
+  it.by_ref().for_each(drop);
+  assert_eq!(it.last(), None);
+
+  let all: IpRange = "*".parse().unwrap();
+  assert_eq!(all.iter().count(), 1 << 32);
+  assert_eq!(all.iter().last(), Some(Ipv4Addr::new(255, 255, 255, 255)));
+}
+
+#[test]
+fn iter_split_at() {
+  let r: IpRange = "10.0.0.0/30".parse().unwrap();
+  let (head, tail) = r.iter().split_at(1);
+  assert_eq!(head.collect::<Vec<_>>(), [Ipv4Addr::new(10, 0, 0, 0)]);
+  assert_eq!(tail.collect::<Vec<_>>(), [Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2), Ipv4Addr::new(10, 0, 0, 3)]);
+
+  let (head, tail) = r.iter().split_at(0);
+  assert_eq!((head.len(), tail.len()), (0, 4));
+  let (head, tail) = r.iter().split_at(9);
+  assert_eq!((head.len(), tail.len()), (4, 0));
+
+  let all: IpRange = "*".parse().unwrap();
+  let (head, tail) = all.iter().split_at(1 << 31);
+  assert_eq!(tail.clone().next(), Some(Ipv4Addr::new(128, 0, 0, 0)));
+  assert_eq!((head.len(), tail.len()), (1 << 31, 1 << 31));
+}
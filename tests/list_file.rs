@@ -0,0 +1,79 @@
+use std::io::Cursor;
+use std::net::Ipv4Addr;
+use netdec::{Aliases, IpRangeError, RangeList};
+
+// ===== range list file tests =====
+
+#[test]
+fn list_comments_and_whitespace() {
+  let text = "\u{feff}# office networks\r\n10.1.0.0/16        ; hq\r\n\r\n    10.2.0.0/16, 10.3.0.0/16\r\n\t!10.1.99.0/24  # lab\r\n;\r\n";
+  let list = RangeList::parse(text);
+  assert!(list.errors.is_empty(), "unexpected errors: {:?}", list.errors);
+
+  let at: Vec<(usize, usize, bool)> = list.entries.iter().map(|e| (e.line, e.column, e.excluded)).collect();
+  assert_eq!(at, vec![(2, 1, false), (4, 5, false), (4, 18, false), (5, 3, true)]);
+  assert_eq!(list.entries[1].range.first, Ipv4Addr::new(10, 2, 0, 0));
+
+  let set = list.to_set();
+  assert_eq!(set.size(), 3 * 65536 - 256);
+  assert!(!set.contains(Ipv4Addr::new(10, 1, 99, 7)));
+}
+
+#[test]
+fn list_error_positions() {
+  let text = "10.0.0.0/8\n  10.0.0.300 # typo\n1.1.1.1, 2.2.2.2/33\n";
+  let list = RangeList::parse(text);
+  assert_eq!(list.entries.len(), 2);
+
+  let at: Vec<String> = list.errors.iter().map(|e| format!("{}:{}", e.line, e.column)).collect();
+  assert_eq!(at, ["2:3", "3:10"]);
+  assert!(list.errors[1].to_string().starts_with("3:10: "));
+}
+
+#[test]
+fn list_from_reader_and_file() {
+  let list = RangeList::from_reader(Cursor::new("10.0.0.{1,3}\n10.0.0.2 ; dns\n")).unwrap();
+  assert_eq!(list.entries.len(), 3);
+  assert_eq!(list.to_set().size(), 3);
+
+  let path = std::env::temp_dir().join(format!("netdec-list-{}.txt", std::process::id()));
+  std::fs::write(&path, "10.0.0.0/24\nnot-a-range\n").unwrap();
+  let list = RangeList::load(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  assert_eq!(list.entries.len(), 1);
+  assert_eq!(list.errors[0].to_string().split(": ").next().unwrap(), format!("{}:2:1", path.display()));
+
+  let err = RangeList::load("/nonexistent/netdec/list.txt").unwrap_err();
+  assert!(matches!(err, IpRangeError::Io(_)));
+}
+
+#[test]
+fn list_aliases_and_patterns() {
+  let aliases = Aliases::parse("office = 10.1.0.0/16, 10.3.0.0/16\n").unwrap();
+  let text = "office      # two ranges\n10.*.0.1, !lab\n";
+
+  let list = RangeList::parse(text);
+  let undefined: Vec<usize> = list.errors.iter().filter(|e| matches!(e.error, IpRangeError::UndefinedAlias(_))).map(|e| e.line).collect();
+  assert_eq!(undefined, [1, 2]);
+
+  let list = RangeList::parse_with_aliases("office\n10.*.0.1\n", &aliases);
+  assert!(list.errors.is_empty(), "unexpected errors: {:?}", list.errors);
+  let at: Vec<(usize, usize)> = list.entries.iter().map(|e| (e.line, e.column)).collect();
+  assert_eq!(at.len(), 2 + 256);
+  assert_eq!(at[..3], [(1, 1), (1, 1), (2, 1)]);
+  assert_eq!(list.to_set().size(), 2 * 65536 + 256 - 2);
+
+  // an undefined alias is reported at its position, the rest of the line still counts
+  let list = RangeList::parse_with_aliases(text, &aliases);
+  assert_eq!(list.errors.len(), 1);
+  assert_eq!((list.errors[0].line, list.errors[0].column), (2, 12));
+  assert_eq!(list.entries.len(), 2 + 256);
+
+  let path = std::env::temp_dir().join(format!("netdec-list-aliases-{}.txt", std::process::id()));
+  std::fs::write(&path, "!office\n10.*.0.1\n").unwrap();
+  let list = RangeList::load_with_aliases(&path, &aliases).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert!(list.errors.is_empty());
+  assert_eq!(list.to_set().size(), 256 - 2);
+}
@@ -0,0 +1,142 @@
+use std::net::Ipv4Addr;
+use netdec::{Aliases, HostBits, IpRange, IpRangeError, IpRangeSet, Notation, ParseOptions, RangeList};
+
+// ===== parse option tests =====
+
+#[test]
+fn default_options_match_parse() {
+  let opts = ParseOptions::default();
+  for input in ["10.0.0.5/24", "10.0.0.1-10.0.0.9", "10.*", "10.0.0.1", "10.0.0.0+16", "10.0.0.0/24[1:3]"] {
+    assert_eq!(IpRange::parse_with(input, &opts).unwrap(), IpRange::parse(input).unwrap());
+  }
+}
+
+#[test]
+fn host_bits_mask() {
+  let r = IpRange::parse_with("10.0.0.5/24", &ParseOptions::new().host_bits(HostBits::Mask)).unwrap();
+  assert_eq!(r.first, Ipv4Addr::new(10, 0, 0, 0));
+}
+
+#[test]
+fn host_bits_reject() {
+  let opts = ParseOptions::new().host_bits(HostBits::Reject);
+  assert!(IpRange::parse_with("10.0.0.0/24", &opts).is_ok());
+
+  match IpRange::parse_with("10.0.0.5/24", &opts) {
+    Err(IpRangeError::HostBitsSet { network, prefix, .. }) => {
+      assert_eq!(network, Ipv4Addr::new(10, 0, 0, 0));
+      assert_eq!(prefix, 24);
+    }
+    other => panic!("expected HostBitsSet, got {other:?}"),
+  }
+}
+
+#[test]
+fn host_bits_reject_applies_to_slice_base() {
+  let opts = ParseOptions::new().host_bits(HostBits::Reject);
+  assert!(matches!(IpRange::parse_with("10.0.0.5/24[0:4]", &opts), Err(IpRangeError::HostBitsSet { .. })));
+}
+
+#[test]
+fn zero_padded() {
+  assert!(IpRange::parse("010.000.000.001").is_err());
+
+  let opts = ParseOptions::new().zero_padded(true);
+  let r = IpRange::parse_with("010.000.000.000/008", &opts).unwrap();
+  assert_eq!(r.first, Ipv4Addr::new(10, 0, 0, 0));
+  assert_eq!(r.prefix, Some(8));
+  assert_eq!(r.input, "010.000.000.000/008");
+
+  let r = IpRange::parse_with("192.168.000.001-192.168.000.010", &opts).unwrap();
+  assert_eq!(r.size, 10);
+}
+
+#[test]
+fn trim() {
+  assert!(IpRange::parse(" 10.0.0.0/8\n").is_err());
+
+  let r = IpRange::parse_with(" 10.0.0.0/8\n", &ParseOptions::new().trim(true)).unwrap();
+  assert_eq!(r.prefix, Some(8));
+  assert_eq!(r.input, " 10.0.0.0/8\n");
+}
+
+#[test]
+fn classful() {
+  let opts = ParseOptions::new().classful(true);
+  assert_eq!(IpRange::parse_with("172.16.0.0", &opts).unwrap().prefix, Some(16));
+  assert_eq!(IpRange::parse_with("172.16.0.0", &opts).unwrap(), IpRange::parse_classful("172.16.0.0").unwrap());
+}
+
+#[test]
+fn disabled_notation() {
+  let opts = ParseOptions::new().notation(Notation::Count, false).notation(Notation::Slice, false);
+  assert!(matches!(IpRange::parse_with("10.0.0.0+4", &opts), Err(IpRangeError::NotationDisabled(Notation::Count))));
+  assert!(matches!(IpRange::parse_with("10.0.0.0/24[0:2]", &opts), Err(IpRangeError::NotationDisabled(Notation::Slice))));
+  assert!(IpRange::parse_with("10.0.0.0/24", &opts).is_ok());
+
+  // re-enabling restores the notation
+  let opts = opts.notation(Notation::Count, true);
+  assert!(opts.allows(Notation::Count));
+  assert!(IpRange::parse_with("10.0.0.0+4", &opts).is_ok());
+}
+
+#[test]
+fn disabled_slice_base() {
+  let opts = ParseOptions::new().notation(Notation::Cidr, false);
+  assert!(matches!(IpRange::parse_with("10.0.0.0/24[0:2]", &opts), Err(IpRangeError::NotationDisabled(Notation::Cidr))));
+}
+
+#[test]
+fn notation_names() {
+  for n in [Notation::Cidr, Notation::Hyphen, Notation::Wildcard, Notation::Single, Notation::Count, Notation::Slice] {
+    assert_eq!(n.to_string().parse::<Notation>(), Ok(n));
+  }
+  assert!("brace".parse::<Notation>().is_err());
+}
+
+#[test]
+fn set_parse_with() {
+  let opts = ParseOptions::new().host_bits(HostBits::Reject);
+  let parsed = IpRangeSet::parse_with("10.0.0.0/24, 10.0.1.5/24", &opts);
+  assert_eq!(parsed.set.size(), 256);
+  assert_eq!(parsed.errors.len(), 1);
+  assert_eq!(parsed.errors[0].item, "10.0.1.5/24");
+}
+
+#[test]
+fn options_reach_expressions() {
+  let opts = ParseOptions::new().host_bits(HostBits::Reject);
+  assert!(matches!(IpRangeSet::eval_with("10.0.0.5/24 | 1.2.3.4", &opts), Err(IpRangeError::HostBitsSet { .. })));
+
+  let opts = ParseOptions::new().zero_padded(true).classful(true);
+  let set = IpRangeSet::eval_with("010.000.000.000 - 010.001.000.000/016", &opts).unwrap();
+  assert_eq!(set.size(), (1 << 24) - (1 << 16));
+}
+
+#[test]
+fn options_reach_lists_and_braces() {
+  let opts = ParseOptions::new().notation(Notation::Count, false).host_bits(HostBits::Reject);
+  let list = RangeList::parse_with("10.0.0.0+4\n10.0.1.0/24, 10.0.{2,3}.1/24\n", &opts);
+  assert_eq!(list.entries.len(), 1);
+  assert!(matches!(list.errors[0].error, IpRangeError::NotationDisabled(Notation::Count)));
+  assert!(matches!(list.errors[1].error, IpRangeError::HostBitsSet { .. }));
+
+  assert!(IpRange::expand_with("10.0.{0,1}.0/24", &opts).is_ok());
+  assert!(matches!(IpRange::expand_with("10.0.0.{0,5}/24", &opts), Err(IpRangeError::HostBitsSet { .. })));
+  let padded: Vec<IpRange> = IpRange::expand_with("010.0.{0,1}.000/24", &ParseOptions::new().zero_padded(true)).unwrap().collect();
+  assert_eq!(padded.len(), 2);
+}
+
+#[test]
+fn options_reach_aliases() {
+  let aliases = Aliases::parse("office01 = 10.0.0.0\nlab = 010.001.000.000/016").unwrap();
+  assert!(aliases.resolve("lab").is_err());
+
+  let aliases = aliases.with_options(ParseOptions::new().classful(true).zero_padded(true));
+  assert_eq!(aliases.resolve("office01").unwrap().size(), 1 << 24);
+  assert_eq!(aliases.resolve("lab").unwrap().size(), 1 << 16);
+
+  // lists and expressions using the aliases share their options
+  assert_eq!(IpRangeSet::parse_with_aliases("office01, 172.16.0.0", &aliases).set.size(), (1 << 24) + (1 << 16));
+  assert_eq!(IpRangeSet::eval_with_aliases("office01 - lab", &aliases).unwrap().size(), (1 << 24) - (1 << 16));
+}
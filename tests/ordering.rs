@@ -0,0 +1,54 @@
+use std::collections::{BTreeSet, HashSet};
+use std::net::Ipv4Addr;
+use std::ops::RangeInclusive;
+use netdec::IpRange;
+
+mod common;
+use common::parse;
+
+// ===== ordering and hashing tests =====
+
+#[test]
+fn semantic_equality() {
+  assert_eq!(parse("10.0.0.0/24"), parse("10.0.0.0-10.0.0.255"));
+  assert_eq!(parse("10.0.0.0/24"), parse("10.0.0.*"));
+  assert_eq!(parse("10.0.0.0/24"), parse("10.0.0.7/24"));
+  assert_ne!(parse("10.0.0.0/24"), parse("10.0.0.0/25"));
+}
+
+#[test]
+fn hash_matches_equality() {
+  let set: HashSet<IpRange> = ["10.0.0.0/24", "10.0.0.0-10.0.0.255", "10.0.0.*", "10.0.1.0/24"]
+    .into_iter()
+    .map(parse)
+    .collect();
+  assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn ordered_by_first_then_last() {
+  let set: BTreeSet<IpRange> = ["10.0.1.0/24", "10.0.0.0/25", "10.0.0.0/24", "9.0.0.0/8"]
+    .into_iter()
+    .map(parse)
+    .collect();
+  let inputs: Vec<&str> = set.iter().map(|r| r.input.as_str()).collect();
+  assert_eq!(inputs, ["9.0.0.0/8", "10.0.0.0/25", "10.0.0.0/24", "10.0.1.0/24"]);
+}
+
+#[test]
+fn u32_interval_conversions() {
+  let r = parse("10.0.0.0/30");
+  let interval: RangeInclusive<u32> = (&r).into();
+  assert_eq!(interval, 0x0a00_0000..=0x0a00_0003);
+  assert_eq!(IpRange::try_from(interval).unwrap(), r);
+
+  assert!(IpRange::try_from(RangeInclusive::new(5u32, 1)).is_err());
+}
+
+#[test]
+fn addr_interval_conversions() {
+  let r = parse("10.0.0.1-10.0.0.9");
+  let interval: RangeInclusive<Ipv4Addr> = r.clone().into();
+  assert_eq!(interval, Ipv4Addr::new(10, 0, 0, 1)..=Ipv4Addr::new(10, 0, 0, 9));
+  assert_eq!(IpRange::try_from(interval).unwrap(), r);
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "rayon")]
+
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeSet};
+use rayon::prelude::*;
+
+// ===== parallel iterator tests =====
+
+#[test]
+fn range_matches_sequential() {
+  let r: IpRange = "10.0.0.5-10.0.200.77".parse().unwrap();
+  let mut par: Vec<Ipv4Addr> = r.par_iter().collect();
+  par.sort();
+  assert_eq!(par, r.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn collect_keeps_order() {
+  let r: IpRange = "10.0.0.0/16".parse().unwrap();
+  let par: Vec<Ipv4Addr> = r.clone().into_par_iter().collect();
+  assert_eq!(par, r.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn large_range() {
+  let r: IpRange = "10.0.0.0/8".parse().unwrap();
+  let sum: u64 = r.par_iter().map(|ip| u32::from(ip) as u64).sum();
+  let n = 1u64 << 24;
+  assert_eq!(sum, n * 0x0a00_0000 + n * (n - 1) / 2);
+  assert_eq!(r.par_iter().filter(|ip| ip.octets()[3] == 0).count(), 1 << 16);
+}
+
+#[test]
+fn single_address() {
+  let r: IpRange = "10.1.2.3".parse().unwrap();
+  assert_eq!(r.par_iter().collect::<Vec<_>>(), [Ipv4Addr::new(10, 1, 2, 3)]);
+}
+
+#[test]
+fn set_matches_sequential() {
+  let set = IpRangeSet::parse("10.0.0.0/20, 10.0.32.1-10.0.40.9, 192.168.1.1").set;
+  let par: Vec<Ipv4Addr> = set.par_iter().collect();
+  let seq: Vec<Ipv4Addr> = set.ranges().flat_map(|r| r.iter()).collect();
+  assert_eq!(par, seq);
+  assert_eq!(set.clone().into_par_iter().count() as u128, set.size());
+}
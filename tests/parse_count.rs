@@ -0,0 +1,43 @@
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeError, RangeKind};
+
+// ===== start plus count notation parsing tests =====
+
+#[test]
+fn count_basic() {
+  let r: IpRange = "10.0.0.0+256".parse().unwrap();
+  assert_eq!(r.first, Ipv4Addr::new(10, 0, 0, 0));
+  assert_eq!(r.last, Ipv4Addr::new(10, 0, 0, 255));
+  assert_eq!(r.size, 256);
+  assert_eq!(r.prefix, Some(24));
+  assert_eq!(r.kind, RangeKind::Count { start: Ipv4Addr::new(10, 0, 0, 0), count: 256 });
+
+  let odd: IpRange = "10.0.0.250+10".parse().unwrap();
+  assert_eq!(odd.last, Ipv4Addr::new(10, 0, 1, 3));
+  assert_eq!(odd.size, 10);
+  assert_eq!(odd.prefix, None);
+}
+
+#[test]
+fn count_edge_cases() {
+  let one: IpRange = "8.8.8.8+1".parse().unwrap();
+  assert_eq!(one.first, one.last);
+
+  let world: IpRange = "0.0.0.0+4294967296".parse().unwrap();
+  assert_eq!(world.last, Ipv4Addr::new(255, 255, 255, 255));
+  assert_eq!(world.size, 1u128 << 32);
+
+  let top: IpRange = "255.255.255.255+1".parse().unwrap();
+  assert_eq!(top.size, 1);
+}
+
+#[test]
+fn count_invalid() {
+  for s in ["255.255.255.255+2", "10.0.0.0+4294967296", "0.0.0.0+99999999999999999999999"] {
+    let err = s.parse::<IpRange>().unwrap_err();
+    assert!(matches!(err, IpRangeError::CountOverflow(_)), "expected overflow for {s}");
+  }
+
+  let err = "10.0.0.0+0".parse::<IpRange>().unwrap_err();
+  assert!(matches!(err, IpRangeError::EmptyRange));
+}
@@ -1,5 +1,5 @@
 use std::net::Ipv4Addr;
-use netdec::{IpRange, IpRangeError};
+use netdec::{IpRange, IpRangeError, RangeKind};
 
 // ===== hyphen notation parsing tests =====
 
@@ -35,3 +35,14 @@ fn hyphen_invalid_range() {
   let err = "1.1.1.10-1.1.1.1".parse::<IpRange>().unwrap_err();
   assert!(matches!(err, IpRangeError::InvalidRangeOrder));
 }
+
+#[test]
+fn hyphen_whole_address_space() {
+  let r: IpRange = "0.0.0.0-255.255.255.255".parse().unwrap();
+  assert_eq!(r.size, 1 << 32);
+  assert_eq!(r.prefix, Some(0));
+  assert_eq!(r.network, Some(Ipv4Addr::new(0, 0, 0, 0)));
+  assert_eq!(r.broadcast, Some(Ipv4Addr::new(255, 255, 255, 255)));
+  assert!(matches!(r.kind, RangeKind::Hyphen { .. }));
+  assert_eq!(r.input, "0.0.0.0-255.255.255.255");
+}
@@ -0,0 +1,82 @@
+use std::net::Ipv4Addr;
+use netdec::{IpPattern, IpRange, IpRangeError, Notation, ParseOptions, RangeKind, WildcardRange};
+
+// ===== wildcard pattern parsing tests =====
+
+#[test]
+fn pattern_sparse() {
+  let p = IpPattern::parse("10.*.0.1").unwrap();
+  assert_eq!(p.octets, [Some(10), None, Some(0), Some(1)]);
+  assert!(!p.is_contiguous());
+  assert_eq!(p.size(), 256);
+  assert!(p.contains(Ipv4Addr::new(10, 42, 0, 1)));
+  assert!(!p.contains(Ipv4Addr::new(10, 42, 0, 2)));
+  assert!(!p.contains(Ipv4Addr::new(11, 42, 0, 1)));
+
+  let hosts = IpPattern::parse("*.*.*.1").unwrap();
+  assert_eq!(hosts.size(), 1 << 24);
+  assert!(hosts.contains(Ipv4Addr::new(8, 8, 8, 1)));
+}
+
+#[test]
+fn pattern_iteration() {
+  let p = IpPattern::parse("10.*.0.1").unwrap();
+  let first3: Vec<_> = p.iter().take(3).collect();
+  assert_eq!(first3, vec![
+    Ipv4Addr::new(10, 0, 0, 1),
+    Ipv4Addr::new(10, 1, 0, 1),
+    Ipv4Addr::new(10, 2, 0, 1),
+  ]);
+  assert_eq!(p.iter().next_back(), Some(Ipv4Addr::new(10, 255, 0, 1)));
+  assert_eq!(p.iter().count(), 256);
+
+  let two = IpPattern::parse("10.*.1.*").unwrap();
+  let got: Vec<_> = two.iter().skip(255).take(2).collect();
+  assert_eq!(got, vec![Ipv4Addr::new(10, 0, 1, 255), Ipv4Addr::new(10, 1, 1, 0)]);
+}
+
+#[test]
+fn pattern_collapses_when_trailing() {
+  match WildcardRange::parse("192.168.*.*").unwrap() {
+    WildcardRange::Contiguous(r) => {
+      assert_eq!(r.kind, RangeKind::Wildcard { specified_octets: 2 });
+      assert_eq!(r.prefix, Some(16));
+    }
+    WildcardRange::Sparse(p) => panic!("expected a contiguous range, got {p:?}"),
+  }
+  assert!(matches!(WildcardRange::parse("10.*").unwrap(), WildcardRange::Contiguous(_)));
+  assert!(matches!(WildcardRange::parse("*.0.*.*").unwrap(), WildcardRange::Sparse(_)));
+
+  // IpRange accepts the contiguous ones only
+  let r: IpRange = "10.0.*.*".parse().unwrap();
+  assert_eq!(r.kind, RangeKind::Wildcard { specified_octets: 2 });
+  let err = "10.*.0.1".parse::<IpRange>().unwrap_err();
+  assert!(matches!(err, IpRangeError::NonContiguous(_)));
+}
+
+#[test]
+fn pattern_exact_size() {
+  let mut it = IpPattern::parse("10.*.0.*").unwrap().iter();
+  assert_eq!(it.len(), 1 << 16);
+  it.next();
+  it.next_back();
+  assert_eq!(it.size_hint(), ((1 << 16) - 2, Some((1 << 16) - 2)));
+  assert_eq!(IpPattern::parse("*.*.*.*").unwrap().iter().len(), 1 << 32);
+
+  let mut it = IpPattern::parse("10.0.0.1").unwrap().iter();
+  it.next();
+  assert_eq!(it.len(), 0);
+}
+
+#[test]
+fn pattern_with_options() {
+  let opts = ParseOptions::new().trim(true).zero_padded(true);
+  let p = IpPattern::parse_with(" 010.*.000.1 ", &opts).unwrap();
+  assert_eq!(p.octets, [Some(10), None, Some(0), Some(1)]);
+  assert_eq!(p.input, " 010.*.000.1 ");
+  assert!(matches!(WildcardRange::parse_with(" 10.*.0.1", &opts).unwrap(), WildcardRange::Sparse(_)));
+
+  let off = ParseOptions::new().notation(Notation::Wildcard, false);
+  assert!(matches!(WildcardRange::parse_with("10.*.0.1", &off), Err(IpRangeError::NotationDisabled(Notation::Wildcard))));
+  assert!(IpPattern::parse(" 10.*.0.1").is_err());
+}
@@ -0,0 +1,56 @@
+use std::net::Ipv4Addr;
+use netdec::{AddressClass, IpRange, RangeKind};
+
+// ===== single address parsing tests =====
+
+#[test]
+fn single_basic() {
+  let r: IpRange = "10.1.2.3".parse().unwrap();
+  assert_eq!(r.kind, RangeKind::Single { addr: Ipv4Addr::new(10, 1, 2, 3) });
+  assert_eq!(r.first, Ipv4Addr::new(10, 1, 2, 3));
+  assert_eq!(r.last, Ipv4Addr::new(10, 1, 2, 3));
+  assert_eq!(r.prefix, Some(32));
+  assert_eq!(r.network, Some(Ipv4Addr::new(10, 1, 2, 3)));
+  assert_eq!(r.size, 1);
+  assert!(r.contains(Ipv4Addr::new(10, 1, 2, 3)));
+  assert!(!r.contains(Ipv4Addr::new(10, 1, 2, 4)));
+}
+
+#[test]
+fn single_invalid() {
+  for s in ["256.0.0.1", "1.2.3", "1.2.3.4.5", "01.2.3.4"] {
+    assert!(s.parse::<IpRange>().is_err(), "should reject {s}");
+  }
+}
+
+// ===== classful prefix inference tests =====
+
+#[test]
+fn classful_inference() {
+  for (input, class, prefix, first, last) in [
+    ("10.0.0.0", AddressClass::A, 8u8, Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 255, 255, 255)),
+    ("172.16.0.0", AddressClass::B, 16, Ipv4Addr::new(172, 16, 0, 0), Ipv4Addr::new(172, 16, 255, 255)),
+    ("192.168.1.7", AddressClass::C, 24, Ipv4Addr::new(192, 168, 1, 0), Ipv4Addr::new(192, 168, 1, 255)),
+  ] {
+    let r = IpRange::parse_classful(input).unwrap();
+    let base: Ipv4Addr = input.parse().unwrap();
+    assert_eq!(r.kind, RangeKind::Classful { base, class }, "kind mismatch for {input}");
+    assert_eq!(r.prefix, Some(prefix), "prefix mismatch for {input}");
+    assert_eq!(r.first, first, "first mismatch for {input}");
+    assert_eq!(r.last, last, "last mismatch for {input}");
+  }
+}
+
+#[test]
+fn classful_without_default_prefix() {
+  // multicast and reserved space stay a single host
+  for (input, class) in [("224.0.0.1", AddressClass::D), ("240.1.2.3", AddressClass::E)] {
+    let r = IpRange::parse_classful(input).unwrap();
+    assert_eq!(r.kind, RangeKind::Classful { base: input.parse().unwrap(), class });
+    assert_eq!(r.size, 1);
+  }
+
+  // other notations are unaffected
+  let cidr = IpRange::parse_classful("10.0.0.0/24").unwrap();
+  assert_eq!(cidr.prefix, Some(24));
+}
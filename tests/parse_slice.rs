@@ -0,0 +1,52 @@
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeError, RangeKind};
+
+// ===== slice notation parsing tests =====
+
+#[test]
+fn slice_basic() {
+  let r: IpRange = "10.0.0.0/24[10:20]".parse().unwrap();
+  assert_eq!(r.first, Ipv4Addr::new(10, 0, 0, 10));
+  assert_eq!(r.last, Ipv4Addr::new(10, 0, 0, 19));
+  assert_eq!(r.size, 10);
+  assert_eq!(r.kind, RangeKind::Slice {
+    from: Ipv4Addr::new(10, 0, 0, 0),
+    to: Ipv4Addr::new(10, 0, 0, 255),
+    start: Some(10),
+    end: Some(20),
+    cidr: Some((Ipv4Addr::new(10, 0, 0, 0), 24)),
+  });
+
+  let aligned: IpRange = "10.0.*[256:512]".parse().unwrap();
+  assert_eq!(aligned.first, Ipv4Addr::new(10, 0, 1, 0));
+  assert_eq!(aligned.prefix, Some(24));
+}
+
+#[test]
+fn slice_negative_and_open() {
+  let tail: IpRange = "10.0.0.0/16[-5:]".parse().unwrap();
+  assert_eq!(tail.first, Ipv4Addr::new(10, 0, 255, 251));
+  assert_eq!(tail.last, Ipv4Addr::new(10, 0, 255, 255));
+
+  let head: IpRange = "10.0.0.9-10.0.0.20[:2]".parse().unwrap();
+  assert_eq!(head.first, Ipv4Addr::new(10, 0, 0, 9));
+  assert_eq!(head.last, Ipv4Addr::new(10, 0, 0, 10));
+
+  let all: IpRange = "192.168.0.0/30[:]".parse().unwrap();
+  assert_eq!(all.size, 4);
+
+  // out of bounds indices are clamped like python slices
+  let clamped: IpRange = "192.168.0.0/30[-100:100]".parse().unwrap();
+  assert_eq!(clamped.size, 4);
+}
+
+#[test]
+fn slice_invalid() {
+  for s in ["10.0.0.0/24[20:10]", "10.0.0.0/24[256:]", "10.0.0.0/24[-1:-1]"] {
+    let err = s.parse::<IpRange>().unwrap_err();
+    assert!(matches!(err, IpRangeError::EmptyRange), "expected empty range for {s}");
+  }
+
+  let err = "10.0.0.5-10.0.0.1[0:1]".parse::<IpRange>().unwrap_err();
+  assert!(matches!(err, IpRangeError::InvalidRangeOrder));
+}
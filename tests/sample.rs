@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+use netdec::{IpRangeError, IpRangeSet};
+
+mod common;
+use common::parse;
+
+// ===== address sampling tests =====
+
+fn set(s: &str) -> IpRangeSet {
+  IpRangeSet::parse(s).set
+}
+
+#[test]
+fn distinct_sorted_and_inside() {
+  for input in ["10.0.0.0/24", "10.0.0.5-10.0.1.77", "0.0.0.0/0", "10.0.0.0/31"] {
+    let r = parse(input);
+    for seed in [0, 1, 42, u64::MAX] {
+      let got = r.sample(10, seed);
+      let unique: HashSet<Ipv4Addr> = got.iter().copied().collect();
+      assert_eq!(got.len() as u128, r.size.min(10), "{input} seed {seed}");
+      assert_eq!(unique.len(), got.len(), "{input} seed {seed}");
+      assert!(got.windows(2).all(|w| w[0] < w[1]));
+      assert!(got.iter().all(|ip| r.contains(*ip)));
+    }
+  }
+}
+
+#[test]
+fn deterministic_per_seed() {
+  let r = parse("10.0.0.0/8");
+  assert_eq!(r.sample(20, 7), r.sample(20, 7));
+  assert_ne!(r.sample(20, 7), r.sample(20, 8));
+}
+
+#[test]
+fn whole_range_when_n_covers_it() {
+  let r = parse("10.0.0.0/28");
+  let all: Vec<Ipv4Addr> = r.iter().collect();
+  assert_eq!(r.sample(16, 3), all);
+  assert_eq!(r.sample(1000, 3), all);
+  assert!(r.sample(0, 3).is_empty());
+}
+
+#[test]
+fn most_of_the_range() {
+  // past half the range the left out addresses are sampled instead
+  let r = parse("10.0.0.0/16");
+  let got = r.sample(65000, 11);
+  assert_eq!(got.len(), 65000);
+  assert!(got.windows(2).all(|w| w[0] < w[1]));
+  assert!(got.iter().all(|ip| r.contains(*ip)));
+  assert_eq!(got, r.sample(65000, 11));
+  assert_ne!(got, r.sample(65000, 12));
+
+  let s = set("10.0.0.0/30 10.0.1.0/30");
+  for seed in 0..50 {
+    let got = s.sample(7, seed);
+    assert_eq!(got.len(), 7);
+    assert!(got.windows(2).all(|w| w[0] < w[1]));
+    assert!(got.iter().all(|ip| s.contains(*ip)));
+  }
+}
+
+#[test]
+fn roughly_uniform() {
+  // every address of a /28 picked about equally often over many seeds
+  let r = parse("10.0.0.0/28");
+  let mut counts = [0u32; 16];
+  for seed in 0..4000 {
+    for ip in r.sample(4, seed) {
+      counts[ip.octets()[3] as usize] += 1;
+    }
+  }
+  // expected 1000 each
+  assert!(counts.iter().all(|&c| (850..1150).contains(&c)), "{counts:?}");
+
+  // same for the complement, 12 of 16 leave out 4
+  let mut counts = [0u32; 16];
+  for seed in 0..1000 {
+    for ip in r.sample(12, seed) {
+      counts[ip.octets()[3] as usize] += 1;
+    }
+  }
+  // expected 750 each
+  assert!(counts.iter().all(|&c| (650..850).contains(&c)), "{counts:?}");
+}
+
+#[test]
+fn set_samples_across_ranges() {
+  let s = set("10.0.0.0-10.0.0.3 192.168.1.10 172.16.0.0/30");
+  let all: Vec<Ipv4Addr> = s.ranges().flat_map(|r| r.iter()).collect();
+  let mut got = s.sample(9, 5);
+  got.sort();
+  let mut expected = all.clone();
+  expected.sort();
+  assert_eq!(got, expected);
+
+  for seed in 0..50 {
+    let got = s.sample(3, seed);
+    assert_eq!(got.len(), 3);
+    assert!(got.iter().all(|ip| s.contains(*ip)));
+  }
+}
+
+#[test]
+fn per_block_one_each() {
+  let r = parse("10.0.0.0/16");
+  let got = r.sample_per_block(24, 1, 42).unwrap();
+  assert_eq!(got.len(), 256);
+  for (i, ip) in got.iter().enumerate() {
+    assert_eq!(ip.octets()[..3], [10, 0, i as u8]);
+  }
+  assert_eq!(got, r.sample_per_block(24, 1, 42).unwrap());
+}
+
+#[test]
+fn per_block_clips_partial_blocks() {
+  // 10.0.0.250-10.0.0.255, 10.0.1.0/24, 10.0.2.0-10.0.2.1
+  let r = parse("10.0.0.250-10.0.2.1");
+  let got = r.sample_per_block(24, 3, 1).unwrap();
+  assert_eq!(got.len(), 8);
+  assert!(got.iter().all(|ip| r.contains(*ip)));
+  assert_eq!(got.iter().filter(|ip| ip.octets()[2] == 2).count(), 2);
+}
+
+#[test]
+fn per_block_merges_ranges_of_a_set() {
+  // both ranges sit in 10.0.0.0/24, so they share one stratum
+  let s = set("10.0.0.1-10.0.0.3 10.0.0.200 10.0.1.7");
+  let got = s.sample_per_block(24, 2, 9).unwrap();
+  assert_eq!(got.len(), 3);
+  assert_eq!(got.iter().filter(|ip| ip.octets()[2] == 0).count(), 2);
+  assert!(got.contains(&Ipv4Addr::new(10, 0, 1, 7)));
+}
+
+#[test]
+fn per_block_whole_range() {
+  let r = parse("10.0.0.0/24");
+  // a /0 block is everything, a /32 block is one address
+  let got = r.sample_per_block(0, 5, 3).unwrap();
+  assert_eq!(got.len(), 5);
+  assert!(got.iter().all(|ip| r.contains(*ip)));
+  assert_eq!(r.sample_per_block(32, 1, 3).unwrap(), r.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn per_block_invalid_prefix() {
+  let r = parse("10.0.0.0/24");
+  assert!(matches!(r.sample_per_block(33, 1, 3), Err(IpRangeError::InvalidPrefix(33))));
+  assert!(matches!(set("10.0.0.0/24").sample_per_block(255, 1, 3), Err(IpRangeError::InvalidPrefix(255))));
+}
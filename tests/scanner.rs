@@ -0,0 +1,85 @@
+use netdec::{matches_range_any, matches_range_pattern, IpRange, IpRangeError};
+
+// ===== scanner and grammar agreement tests =====
+
+// fragments that hit the edges of every notation in the grammar
+const TOKENS: &[&str] = &[
+  "0", "1", "9", "00", "01", "10", "25", "99", "199", "249", "255", "256", "300", "1000",
+  "3", "30", "32", "33", "18446744073709551616", "9223372036854775808",
+  ".", ".", ".", "*", "/", "-", "+", "[", "]", ":", " ", "x",
+  "10.0.0.0", "1.2.3.4", "255.255.255.255", "10.*", "[1:2]", "[-1:]", "[:]",
+];
+
+// xorshift, fixed seeds so failures reproduce
+struct Rng(u64);
+
+impl Rng {
+  fn next(&mut self) -> u64 {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    self.0
+  }
+
+  fn part(&mut self) -> String {
+    match self.next() % 4 {
+      0 => "*".to_string(),
+      1 => format!("0{}", self.next() % 10),
+      _ => (self.next() % 300).to_string(),
+    }
+  }
+}
+
+fn grammar_accepts(s: &str) -> bool {
+  matches_range_any(s) || matches_range_pattern(s)
+}
+
+fn scanner_accepts(s: &str) -> bool {
+  !matches!(IpRange::parse(s), Err(IpRangeError::Syntax(_)))
+}
+
+fn assert_same_verdict(s: &str) {
+  assert_eq!(scanner_accepts(s), grammar_accepts(s), "verdicts differ for {s:?}");
+}
+
+#[test]
+fn known_inputs() {
+  for s in [
+    "", "*", "10.*", "10.0.*", "10.0.0.*", "10.0.0.0.*", "*.0", "*.*", "*.*.*.*", "10.*.0.1", "10.*.*",
+    "10.0.0.0/24", "10.0.0.0/0", "10.0.0.0/32", "10.0.0.0/33", "10.0.0.0/024", "10.0.0.0/",
+    "10.0.0.1-10.0.0.9", "10.0.0.1-10.0.0", "10.0.0.1-10.0.0.*", "10.0.0.1-",
+    "10.0.0.0+0", "10.0.0.0+00", "10.0.0.0+18446744073709551616", "10.0.0.0+",
+    "10.0.0.0/24[1:2]", "10.0.0.0/24[:]", "10.0.0.0/24[-0:-00]", "10.0.0.0/24[1]", "10.0.0.1[0:1]",
+    "10.*[1:2]", "10.*.*.*[1:2]", "10.0.0.0+4[0:1]", "10.0.0.1-10.0.0.9[-9223372036854775809:]",
+    "01.0.0.0", "10.0.0.256", "10.0.0.0.0", "10..0.0", " 10.0.0.0", "10.0.0.0 ",
+  ] {
+    assert_same_verdict(s);
+  }
+}
+
+#[test]
+fn generated_inputs() {
+  let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+  for _ in 0..50_000 {
+    let len = 1 + rng.next() % 9;
+    let s: String = (0..len).map(|_| TOKENS[(rng.next() % TOKENS.len() as u64) as usize]).collect();
+    assert_same_verdict(&s);
+  }
+}
+
+#[test]
+fn generated_addresses() {
+  // well formed shapes with random numbers
+  let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+  for _ in 0..20_000 {
+    let a = [rng.part(), rng.part(), rng.part(), rng.part()].join(".");
+    let s = match rng.next() % 5 {
+      0 => a,
+      1 => format!("{a}/{}", rng.next() % 40),
+      2 => format!("{a}-{}", [rng.part(), rng.part(), rng.part(), rng.part()].join(".")),
+      3 => format!("{a}+{}", rng.next() % 1000),
+      _ => format!("{a}/{}[{}:-{}]", rng.next() % 33, rng.next() % 10, rng.next() % 10),
+    };
+    assert_same_verdict(&s);
+  }
+}
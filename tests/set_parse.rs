@@ -0,0 +1,86 @@
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeError, IpRangeSet};
+
+mod common;
+use common::bounds;
+
+// ===== range list parsing tests =====
+
+#[test]
+fn list_with_exclusions() {
+  let parsed = IpRangeSet::parse("10.0.0.0/24, 10.0.1.5-10.0.1.9, !10.0.0.1");
+  assert!(parsed.errors.is_empty());
+  assert_eq!(bounds(&parsed.set), vec![
+    (Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 0)),
+    (Ipv4Addr::new(10, 0, 0, 2), Ipv4Addr::new(10, 0, 0, 255)),
+    (Ipv4Addr::new(10, 0, 1, 5), Ipv4Addr::new(10, 0, 1, 9)),
+  ]);
+  assert_eq!(parsed.set.size(), 260);
+
+  // exclusions apply regardless of their position, "-" works like "!"
+  let parsed = IpRangeSet::parse("-10.0.0.128/25 10.0.0.0/24\t10.0.0.64+64");
+  assert_eq!(bounds(&parsed.set), vec![(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 127))]);
+
+  let parsed = IpRangeSet::parse("10.{0,1}.0.0/16, ! 10.1.*");
+  assert!(parsed.errors.is_empty());
+  assert_eq!(bounds(&parsed.set), vec![(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 255, 255))]);
+}
+
+#[test]
+fn list_merges_overlaps() {
+  let parsed = IpRangeSet::parse("10.0.0.0/25,10.0.0.128/25,10.0.0.100-10.0.1.0");
+  assert_eq!(bounds(&parsed.set), vec![(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 1, 0))]);
+
+  let parsed = IpRangeSet::parse("");
+  assert!(parsed.set.is_empty());
+  assert!(parsed.errors.is_empty());
+}
+
+#[test]
+fn list_sparse_patterns() {
+  // one range per run, trailing wildcards widen the runs
+  let parsed = IpRangeSet::parse("10.*.0.1, 192.168.*.*, !10.128.0.0/9");
+  assert!(parsed.errors.is_empty());
+  assert_eq!(parsed.set.size(), 128 + 65536);
+  assert_eq!(bounds(&parsed.set)[..2], [
+    (Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 1)),
+    (Ipv4Addr::new(10, 1, 0, 1), Ipv4Addr::new(10, 1, 0, 1)),
+  ]);
+
+  let parsed = IpRangeSet::parse("*.0.*.*");
+  assert_eq!(parsed.set.ranges().count(), 256);
+  assert_eq!(parsed.set.ranges().nth(1).map(|r| (r.first, r.last)), Some((Ipv4Addr::new(1, 0, 0, 0), Ipv4Addr::new(1, 0, 255, 255))));
+
+  // patterns are not ranges, so other notations cannot follow them
+  let parsed = IpRangeSet::parse("10.*.0.1/24");
+  assert_eq!(parsed.errors.len(), 1);
+}
+
+#[test]
+fn list_item_errors() {
+  let input = "10.0.0.0/24, 10.0.0.300, !1.1.1.9-1.1.1.1";
+  let parsed = IpRangeSet::parse(input);
+  assert_eq!(parsed.set.size(), 256);
+  assert_eq!(parsed.errors.len(), 2);
+
+  assert_eq!(parsed.errors[0].item, "10.0.0.300");
+  assert_eq!(parsed.errors[0].offset, 13);
+  assert_eq!(parsed.errors[1].item, "1.1.1.9-1.1.1.1");
+  assert_eq!(&input[parsed.errors[1].offset..], "1.1.1.9-1.1.1.1");
+  assert!(matches!(parsed.errors[1].error, IpRangeError::InvalidRangeOrder));
+}
+
+#[test]
+fn set_operations() {
+  let a = IpRangeSet::parse("10.0.0.0/24").set;
+  let b = IpRangeSet::parse("10.0.0.128/25, 10.0.1.0/24").set;
+
+  assert_eq!(a.union(&b).size(), 512);
+  assert_eq!(bounds(&a.difference(&b)), vec![(Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 127))]);
+
+  let mut c = a.clone();
+  c.remove(&"10.0.0.10-10.0.0.19".parse::<IpRange>().unwrap());
+  assert_eq!(c.size(), 246);
+  assert!(!c.contains(Ipv4Addr::new(10, 0, 0, 15)));
+  assert!(c.contains(Ipv4Addr::new(10, 0, 0, 20)));
+}
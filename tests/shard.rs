@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeError, IpRangeSet, ShardStrategy};
+
+// ===== sharding tests =====
+
+const STRATEGIES: [ShardStrategy; 4] = [
+  ShardStrategy::Contiguous,
+  ShardStrategy::Interleaved,
+  ShardStrategy::Shuffled(0),
+  ShardStrategy::Shuffled(1234),
+];
+
+fn assert_exact_cover(all: &[Ipv4Addr], shards: Vec<Vec<Ipv4Addr>>) {
+  let total: usize = shards.iter().map(Vec::len).sum();
+  let union: HashSet<Ipv4Addr> = shards.into_iter().flatten().collect();
+  assert_eq!(total, all.len(), "shards overlap or miss addresses");
+  assert_eq!(union, all.iter().copied().collect());
+}
+
+#[test]
+fn range_shards_cover_exactly_once() {
+  let r: IpRange = "10.0.0.5-10.0.3.77".parse().unwrap();
+  let all: Vec<Ipv4Addr> = r.iter().collect();
+
+  for strategy in STRATEGIES {
+    for n in [1, 2, 3, 7, 64, 2000] {
+      let shards = (0..n).map(|i| r.shard_with(i, n, strategy).unwrap().collect()).collect();
+      assert_exact_cover(&all, shards);
+    }
+  }
+}
+
+#[test]
+fn set_shards_cover_exactly_once() {
+  let set = IpRangeSet::parse("10.0.0.0/28, 10.0.1.3-10.0.1.9, 192.168.0.1").set;
+  let all: Vec<Ipv4Addr> = set.ranges().flat_map(|r| r.iter()).collect();
+
+  for strategy in STRATEGIES {
+    for n in [1, 2, 5, 40] {
+      let shards = (0..n).map(|i| set.shard_with(i, n, strategy).unwrap().collect()).collect();
+      assert_exact_cover(&all, shards);
+    }
+  }
+}
+
+#[test]
+fn contiguous_shards() {
+  let r: IpRange = "10.0.0.0/29".parse().unwrap();
+  let shards: Vec<Vec<Ipv4Addr>> = (0..3).map(|i| r.shard(i, 3).unwrap().collect()).collect();
+  assert_eq!(shards[0], [Ipv4Addr::new(10, 0, 0, 0), Ipv4Addr::new(10, 0, 0, 1)]);
+  assert_eq!(shards[2].len(), 3);
+  assert_eq!(r.shard(1, 3).unwrap().size_hint(), (3, Some(3)));
+}
+
+#[test]
+fn interleaved_shards() {
+  let r: IpRange = "10.0.0.0/29".parse().unwrap();
+  let shard: Vec<Ipv4Addr> = r.shard_with(1, 3, ShardStrategy::Interleaved).unwrap().collect();
+  assert_eq!(shard, [Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 4), Ipv4Addr::new(10, 0, 0, 7)]);
+}
+
+#[test]
+fn shuffled_shards_follow_the_shuffled_order() {
+  // every shard is a subsequence of the same shuffled walk
+  let r: IpRange = "10.0.0.0/24".parse().unwrap();
+  let order: Vec<Ipv4Addr> = r.iter_shuffled(5).collect();
+  for i in 0..4 {
+    let shard: Vec<Ipv4Addr> = r.shard_with(i, 4, ShardStrategy::Shuffled(5)).unwrap().collect();
+    let positions: Vec<usize> = shard.iter().map(|ip| order.iter().position(|o| o == ip).unwrap()).collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+  }
+}
+
+#[test]
+fn invalid_shard() {
+  let r: IpRange = "10.0.0.0/24".parse().unwrap();
+  assert!(matches!(r.shard(4, 4), Err(IpRangeError::InvalidShard { index: 4, count: 4 })));
+  assert!(matches!(r.shard(0, 0), Err(IpRangeError::InvalidShard { .. })));
+}
+
+#[test]
+fn empty_set() {
+  let set = IpRangeSet::new();
+  for strategy in STRATEGIES {
+    assert_eq!(set.shard_with(0, 2, strategy).unwrap().count(), 0);
+  }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+
+mod common;
+use common::parse;
+
+// ===== shuffled iteration tests =====
+
+#[test]
+fn visits_every_address_once() {
+  for input in ["10.0.0.0/24", "10.0.0.5-10.0.1.77", "10.0.0.1", "10.0.0.0/31", "10.0.0.0+3"] {
+    let r = parse(input);
+    for seed in [0, 1, 42, u64::MAX] {
+      let got: Vec<Ipv4Addr> = r.iter_shuffled(seed).collect();
+      let unique: HashSet<Ipv4Addr> = got.iter().copied().collect();
+      assert_eq!(got.len() as u128, r.size, "{input} seed {seed}");
+      assert_eq!(unique.len(), got.len(), "{input} seed {seed}");
+      assert!(got.iter().all(|ip| r.contains(*ip)));
+    }
+  }
+}
+
+#[test]
+fn deterministic_per_seed() {
+  let r = parse("10.0.0.0/20");
+  let a: Vec<Ipv4Addr> = r.iter_shuffled(7).collect();
+  let b: Vec<Ipv4Addr> = r.iter_shuffled(7).collect();
+  let c: Vec<Ipv4Addr> = r.iter_shuffled(8).collect();
+  assert_eq!(a, b);
+  assert_ne!(a, c);
+
+  // not simply sequential
+  let sequential: Vec<Ipv4Addr> = r.iter().collect();
+  assert_ne!(a, sequential);
+}
+
+#[test]
+fn resumes_from_saved_position() {
+  let r = parse("10.0.0.0/16");
+  let mut it = r.iter_shuffled(99);
+  let head: Vec<Ipv4Addr> = it.by_ref().take(1000).collect();
+  let saved = it.position();
+  let rest: Vec<Ipv4Addr> = it.collect();
+
+  let resumed: Vec<Ipv4Addr> = r.iter_shuffled(99).resume_at(saved).collect();
+  assert_eq!(resumed, rest);
+  assert_eq!(head.len() + rest.len(), 1 << 16);
+
+  let mut done = r.iter_shuffled(99).resume_at(u64::MAX);
+  assert_eq!(done.next(), None);
+}
+
+#[test]
+fn whole_address_space_is_lazy() {
+  let all = parse("*");
+  let first: Vec<Ipv4Addr> = all.iter_shuffled(1).take(5).collect();
+  assert_eq!(first.len(), 5);
+
+  let mut it = all.iter_shuffled(1).resume_at(1 << 31);
+  assert!(it.next().is_some());
+}
@@ -0,0 +1,87 @@
+use std::net::Ipv4Addr;
+use netdec::{IpRange, IpRangeSet, ParseOptions, ParseWarning, RangeList};
+
+// ===== parse warning tests =====
+
+fn warnings(input: &str) -> Vec<ParseWarning> {
+  IpRange::parse_with_warnings(input, &ParseOptions::default()).unwrap().warnings
+}
+
+#[test]
+fn clean_input() {
+  for input in ["10.0.0.0/24", "10.0.0.1-10.0.0.9", "10.0.*", "10.0.0.0/8", "10.0.0.255"] {
+    assert!(warnings(input).is_empty(), "{input}");
+  }
+}
+
+#[test]
+fn host_bits_set() {
+  let parsed = IpRange::parse_with_warnings("10.0.0.5/24", &ParseOptions::default()).unwrap();
+  assert_eq!(parsed.range.first, Ipv4Addr::new(10, 0, 0, 0));
+  assert_eq!(parsed.warnings, vec![ParseWarning::HostBitsSet {
+    base: Ipv4Addr::new(10, 0, 0, 5),
+    network: Ipv4Addr::new(10, 0, 0, 0),
+    prefix: 24,
+  }]);
+}
+
+#[test]
+fn host_bits_set_in_slice_base() {
+  assert_eq!(warnings("10.0.0.5/24[0:5]"), vec![ParseWarning::HostBitsSet {
+    base: Ipv4Addr::new(10, 0, 0, 5),
+    network: Ipv4Addr::new(10, 0, 0, 0),
+    prefix: 24,
+  }]);
+  assert!(warnings("10.0.0.0/24[0:5]").is_empty());
+  assert!(warnings("10.0.0.5-10.0.0.9[0:2]").is_empty());
+}
+
+#[test]
+fn covers_everything() {
+  assert_eq!(warnings("0.0.0.0/0"), vec![ParseWarning::CoversEverything]);
+  assert_eq!(warnings("*"), vec![ParseWarning::CoversEverything]);
+  assert_eq!(warnings("0.0.0.0-255.255.255.255"), vec![ParseWarning::CoversEverything]);
+}
+
+#[test]
+fn huge_enumeration() {
+  assert_eq!(warnings("10.0.0.0/7"), vec![ParseWarning::HugeEnumeration { size: 1 << 25 }]);
+  assert!(warnings("10.*").is_empty());
+}
+
+#[test]
+fn starts_on_broadcast() {
+  let start = Ipv4Addr::new(10, 0, 0, 255);
+  assert_eq!(warnings("10.0.0.255-10.0.1.9"), vec![ParseWarning::StartsOnBroadcast { start }]);
+  assert!(warnings("10.0.0.255-10.0.0.255").is_empty());
+}
+
+#[test]
+fn display() {
+  assert_eq!(warnings("10.0.0.5/24")[0].to_string(), "host bits set in 10.0.0.5/24, using 10.0.0.0/24");
+}
+
+#[test]
+fn list_warnings() {
+  let parsed = IpRangeSet::parse_with("10.0.0.0/24, !10.0.0.5/24 10.0.{1,2}.0/24 *", &ParseOptions::default());
+  assert!(parsed.errors.is_empty());
+  let found: Vec<(&str, usize)> = parsed.warnings.iter().map(|w| (w.item.as_str(), w.offset)).collect();
+  assert_eq!(found, vec![("10.0.0.5/24", 14), ("*", 42)]);
+  assert_eq!(parsed.warnings[1].warning, ParseWarning::CoversEverything);
+  assert_eq!(parsed.warnings[0].to_string(), "10.0.0.5/24 (at 14): host bits set in 10.0.0.5/24, using 10.0.0.0/24");
+
+  let list = RangeList::parse("10.0.0.0/24\n  10.0.0.255-10.0.1.9 # gap\n");
+  assert_eq!(list.warnings.len(), 1);
+  assert_eq!((list.warnings[0].line, list.warnings[0].column), (2, 3));
+  assert_eq!(list.warnings[0].warning, ParseWarning::StartsOnBroadcast { start: Ipv4Addr::new(10, 0, 0, 255) });
+}
+
+#[test]
+fn expression_warnings() {
+  let evaluated = IpRangeSet::eval_with_warnings("(0.0.0.0/0 - 10.0.0.5/8) | 10.0.0.0/24", &ParseOptions::default()).unwrap();
+  assert_eq!(evaluated.set, IpRangeSet::eval("~10.0.0.0/8 | 10.0.0.0/24").unwrap());
+  let found: Vec<(&str, usize)> = evaluated.warnings.iter().map(|w| (w.item.as_str(), w.offset)).collect();
+  assert_eq!(found, vec![("0.0.0.0/0", 1), ("10.0.0.5/8", 13)]);
+
+  assert!(IpRangeSet::eval_with_warnings("10.0.0.0/8 - 10.*.0.1", &ParseOptions::default()).unwrap().warnings.is_empty());
+}